@@ -0,0 +1,95 @@
+// ============================================================================
+// Embedded Verification Keys
+// ============================================================================
+// Reads the Groth16 verification keys exported for the circuits under
+// `circuits/` (`target/vk.json` next to each `Nargo.toml`, in the snarkjs
+// JSON layout) and writes them out as const data for `src/embedded.rs`.
+// A circuit that has not been compiled yet is embedded as `None`, so the
+// contract still builds from a clean checkout.
+//
+// `BATTLESHIP_CIRCUITS_DIR` overrides where the circuits are looked for.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+/// Circuits to embed: constant name, directory relative to `circuits/`.
+const CIRCUITS: [(&str, &str); 2] = [("FLEET", "fleet"), ("SHOT", "")];
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=BATTLESHIP_CIRCUITS_DIR");
+    let circuits = env::var_os("BATTLESHIP_CIRCUITS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../circuits"));
+
+    let mut out = String::new();
+    for (name, dir) in CIRCUITS {
+        let path = circuits.join(dir).join("target/vk.json");
+        println!("cargo:rerun-if-changed={}", path.display());
+        let key = match fs::read_to_string(&path) {
+            Ok(json) => {
+                let key = parse_key(&json).unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+                format!("Some({key})")
+            }
+            Err(_) => "None".into(),
+        };
+        writeln!(out, "pub const {name}: Option<EmbeddedKey> = {key};").unwrap();
+    }
+
+    let dest = Path::new(&env::var("OUT_DIR").unwrap()).join("embedded_vk.rs");
+    fs::write(dest, out).unwrap();
+}
+
+/// Render a snarkjs `verification_key.json` as an `EmbeddedKey` literal,
+/// with points in the contract's `bn254` encoding.
+fn parse_key(json: &str) -> Result<String, String> {
+    let vk: Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+    if vk["protocol"] != "groth16" || vk["curve"] != "bn128" {
+        return Err("not a Groth16 key over BN254".into());
+    }
+    let ic = vk["IC"].as_array().ok_or("missing IC")?;
+    let ic: Vec<String> = ic.iter().map(g1).collect::<Result<_, _>>()?;
+    Ok(format!(
+        "EmbeddedKey {{ alpha: {}, beta: {}, gamma: {}, delta: {}, ic: &[{}] }}",
+        g1(&vk["vk_alpha_1"])?,
+        g2(&vk["vk_beta_2"])?,
+        g2(&vk["vk_gamma_2"])?,
+        g2(&vk["vk_delta_2"])?,
+        ic.join(", "),
+    ))
+}
+
+/// Affine G1 point `[x, y, "1"]` as `x || y`.
+fn g1(point: &Value) -> Result<String, String> {
+    let bytes = [field(&point[0])?, field(&point[1])?].concat();
+    Ok(format!("{bytes:?}"))
+}
+
+/// Affine G2 point `[[x0, x1], [y0, y1], ["1", "0"]]` as
+/// `x1 || x0 || y1 || y0`, the Ethereum precompile order.
+fn g2(point: &Value) -> Result<String, String> {
+    let (x, y) = (&point[0], &point[1]);
+    let bytes = [field(&x[1])?, field(&x[0])?, field(&y[1])?, field(&y[0])?].concat();
+    Ok(format!("{bytes:?}"))
+}
+
+/// Decimal field element string as 32 big-endian bytes.
+fn field(value: &Value) -> Result<[u8; 32], String> {
+    let digits = value.as_str().ok_or("coordinate is not a string")?;
+    let mut bytes = [0u8; 32];
+    for digit in digits.chars() {
+        let mut carry = digit.to_digit(10).ok_or_else(|| format!("bad coordinate {digits}"))?;
+        for byte in bytes.iter_mut().rev() {
+            let value = *byte as u32 * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return Err(format!("coordinate {digits} exceeds 256 bits"));
+        }
+    }
+    Ok(bytes)
+}
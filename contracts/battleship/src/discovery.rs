@@ -0,0 +1,29 @@
+// ============================================================================
+// Game Discovery
+// ============================================================================
+// Battleship's answer to the shared `game_metadata()` discovery interface
+// (see `zk_game::discovery`).
+
+use soroban_sdk::{vec, Bytes, Env, String, Symbol};
+
+pub use zk_game::discovery::{GameDiscovery, GameDiscoveryClient, GameMetadata};
+
+/// Canonical game name
+pub const GAME_NAME: &str = "ZK Battleship";
+/// Where the full rules are published; `rules_hash` commits to this URI
+pub const RULES_URI: &str = "https://github.com/harshbpathak/zk-battleship#game-flow";
+/// Identifier of the Noir circuit proofs must be generated with
+pub const CIRCUIT_ID: &str = "battleship_shot_v1";
+
+/// Build this contract's metadata.
+pub fn metadata(env: &Env) -> GameMetadata {
+    let rules_hash = env.crypto().sha256(&Bytes::from_slice(env, RULES_URI.as_bytes()));
+    GameMetadata {
+        name: String::from_str(env, GAME_NAME),
+        version: String::from_str(env, env!("CARGO_PKG_VERSION")),
+        variants: vec![env, Symbol::new(env, "classic")],
+        rules_uri: String::from_str(env, RULES_URI),
+        rules_hash: rules_hash.to_bytes(),
+        circuit_id: Symbol::new(env, CIRCUIT_ID),
+    }
+}
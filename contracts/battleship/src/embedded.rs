@@ -0,0 +1,37 @@
+// ============================================================================
+// Embedded Verification Keys
+// ============================================================================
+// The verification keys of the circuits this build was compiled against,
+// embedded by `build.rs` from `circuits/*/target/vk.json`. They are never
+// used to verify; they let a client check that the keys installed on-chain
+// are the ones for the circuits it proves with.
+
+use soroban_sdk::{BytesN, Env, Vec};
+use zk_game::groth16::VerificationKey;
+
+/// A verification key as const data, points in the `bn254` encoding.
+pub struct EmbeddedKey {
+    pub alpha: [u8; 64],
+    pub beta: [u8; 128],
+    pub gamma: [u8; 128],
+    pub delta: [u8; 128],
+    pub ic: &'static [[u8; 64]],
+}
+
+include!(concat!(env!("OUT_DIR"), "/embedded_vk.rs"));
+
+impl EmbeddedKey {
+    pub fn to_key(&self, env: &Env) -> VerificationKey {
+        let mut ic = Vec::new(env);
+        for point in self.ic {
+            ic.push_back(BytesN::from_array(env, point));
+        }
+        VerificationKey {
+            alpha: BytesN::from_array(env, &self.alpha),
+            beta: BytesN::from_array(env, &self.beta),
+            gamma: BytesN::from_array(env, &self.gamma),
+            delta: BytesN::from_array(env, &self.delta),
+            ic,
+        }
+    }
+}
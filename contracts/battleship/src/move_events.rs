@@ -0,0 +1,240 @@
+// ============================================================================
+// Move Events
+// ============================================================================
+// Every event of a game, from its creation through each move to its
+// settlement, is published under the topics ("bs", session_id, name), so an
+// indexer following one session filters on the first two topics instead of
+// decoding every game's events. Each payload is a struct whose `schema`
+// field is `EVENT_SCHEMA`, bumped whenever any of them changes shape.
+//
+// Moves: `fire`, `bombard` and `sonar` (`ShotFired`), `respond`
+// (`ShotAnswered`), `bombarded` (`AreaAnswered`), `scanned`
+// (`ScanAnswered`), `sunk` (`ShipSunk`), `mine` (`MineStruck`) and `strike`
+// (`Strike`).
+//
+// Lifecycle: `init` (`GameCreated`), `wager` (`WagerSet`), `commit`,
+// `coin_cmt`, `coin_rvl`, `coin_forf`, `shot_cmt`, `cancel_rq`,
+// `reset_req`, `resign` and `draw_offr` (`PlayerActed`), `restored`
+// (`GameRestored`), `challenge` (`Challenged`), `upheld` (`Upheld`),
+// `timeout` (`TimedOut`), `settled` (`Settled`), `slashed` (`Slashed`),
+// `rematch` (`RematchRequested`), `solved` (`PuzzleSolved`) and `reveal`
+// (`FleetRevealed`).
+//
+// Deployment-wide events, such as service and key changes, are not a
+// session's and keep their own topics.
+
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, IntoVal, Symbol, Val};
+
+use crate::storage::GameStore;
+use crate::{GamePhase, ScanLine, ShotKind};
+
+/// First topic of every move event
+pub const EVENT_PREFIX: Symbol = symbol_short!("bs");
+/// Version of the move event payloads
+pub const EVENT_SCHEMA: u32 = 1;
+
+/// A shot, bombardment or sonar scan fired.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShotFired {
+    pub schema: u32,
+    pub attacker: Address,
+    pub x: u32,
+    pub y: u32,
+    /// Number of the shot in the game, 0 for a wasted simultaneous shot
+    pub turn: u32,
+    pub kind: ShotKind,
+}
+
+/// A single shot answered.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShotAnswered {
+    pub schema: u32,
+    pub defender: Address,
+    pub x: u32,
+    pub y: u32,
+    pub turn: u32,
+    pub hit: bool,
+    /// The response as proven: 1 = hit, 0 = miss, `RESPONSE_MINED` = mine
+    pub response: u32,
+}
+
+/// A bombardment answered.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AreaAnswered {
+    pub schema: u32,
+    pub defender: Address,
+    /// Centre of the area
+    pub x: u32,
+    pub y: u32,
+    pub turn: u32,
+    /// Ship cells hit in the area
+    pub hits: u32,
+}
+
+/// A sonar scan answered.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScanAnswered {
+    pub schema: u32,
+    pub defender: Address,
+    pub line: ScanLine,
+    pub index: u32,
+    pub turn: u32,
+    /// Ship cells in the line
+    pub count: u32,
+}
+
+/// A ship announced sunk.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShipSunk {
+    pub schema: u32,
+    pub defender: Address,
+    pub turn: u32,
+    /// Ship id, see `SHIP_SIZES`
+    pub ship: u32,
+    pub length: u32,
+}
+
+/// A mine struck.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MineStruck {
+    pub schema: u32,
+    pub defender: Address,
+    pub x: u32,
+    pub y: u32,
+    pub turn: u32,
+}
+
+/// An invalid response counted against its defender.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Strike {
+    pub schema: u32,
+    pub defender: Address,
+    pub turn: u32,
+    /// Strikes the defender has now
+    pub strikes: u32,
+}
+
+/// A game set up between two seats.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameCreated {
+    pub schema: u32,
+    pub player1: Address,
+    pub player2: Address,
+}
+
+/// Wager terms set.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WagerSet {
+    pub schema: u32,
+    pub token: Address,
+    /// Stake per player
+    pub amount: i128,
+}
+
+/// A step a player took outside play: committing a fleet, coin or shot,
+/// forfeiting a coin flip, or asking to cancel, reset, resign or draw.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlayerActed {
+    pub schema: u32,
+    pub player: Address,
+}
+
+/// A lapsed game restored from its snapshot.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameRestored {
+    pub schema: u32,
+    /// Phase play resumes in
+    pub phase: GamePhase,
+}
+
+/// An optimistic answer challenged.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Challenged {
+    pub schema: u32,
+    pub attacker: Address,
+    pub defender: Address,
+    pub turn: u32,
+}
+
+/// A challenged answer proven.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Upheld {
+    pub schema: u32,
+    pub defender: Address,
+    pub turn: u32,
+}
+
+/// A game won because the opponent ran out of time.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimedOut {
+    pub schema: u32,
+    pub winner: Address,
+    pub loser: Address,
+}
+
+/// A game settled with one aggregate proof.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Settled {
+    pub schema: u32,
+    pub winner: Address,
+    pub transcript_hash: BytesN<32>,
+}
+
+/// A cheater's bond paid to their victim.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Slashed {
+    pub schema: u32,
+    pub cheater: Address,
+    pub victim: Address,
+    pub amount: i128,
+}
+
+/// A rematch asked for under `rematch_id`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RematchRequested {
+    pub schema: u32,
+    pub player: Address,
+    pub rematch_id: u32,
+}
+
+/// A puzzle solved well enough for its leaderboard.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PuzzleSolved {
+    pub schema: u32,
+    pub puzzle_id: u32,
+    pub player: Address,
+    pub shots: u32,
+}
+
+/// A fleet revealed after the game and audited.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FleetRevealed {
+    pub schema: u32,
+    pub player: Address,
+    /// Whether the audit found a false response or a misplaced fleet
+    pub flagged: bool,
+}
+
+/// Publish event `name` of `game` with `payload`.
+pub fn publish(game: &GameStore, name: Symbol, payload: impl IntoVal<Env, Val>) {
+    game.env().events().publish((EVENT_PREFIX, game.session_id(), name), payload);
+}
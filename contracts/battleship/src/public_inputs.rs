@@ -0,0 +1,194 @@
+// ============================================================================
+// Shot Proof Public Inputs
+// ============================================================================
+// The public inputs of a shot response proof: the `INPUT_*` names a circuit
+// version's layout lists them by, the field element the contract gives
+// each, and the packing of those values into the layout's order. The
+// contract verifies with `ShotInputs::pack`, and off-chain provers built on
+// this crate pack their inputs the same way, so the two agree on the layout
+// by construction rather than by keeping two copies in step.
+//
+// `CIRCUIT_LAYOUT` is the order `circuits/src/main.nr` declares its public
+// parameters in; registered circuit versions may order them differently.
+
+use soroban_sdk::{symbol_short, xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol, Vec};
+use zk_game::bn254::Fr;
+use zk_game::poseidon2;
+use zk_game::verifier::MAX_PUBLIC_INPUTS;
+
+use crate::{BattleshipContract, CommitmentScheme};
+
+/// Session being answered
+pub const INPUT_SESSION: Symbol = symbol_short!("session");
+/// Number of the shot being answered, counting from 1 each game
+pub const INPUT_TURN: Symbol = symbol_short!("turn");
+/// This contract, as sha256 of its address XDR reduced into the field
+pub const INPUT_CONTRACT: Symbol = symbol_short!("contract");
+/// Defender's fleet commitment
+pub const INPUT_COMMITMENT: Symbol = symbol_short!("commit");
+pub const INPUT_SHOT_X: Symbol = symbol_short!("shot_x");
+pub const INPUT_SHOT_Y: Symbol = symbol_short!("shot_y");
+/// 1 = hit, 0 = miss
+pub const INPUT_RESPONSE: Symbol = symbol_short!("response");
+/// Cells of the defender's board hit so far, this shot included, as a
+/// bitmask with bit `x * 10 + y` per cell
+pub const INPUT_HITS: Symbol = symbol_short!("hits");
+/// Id of the ship the shot sank, 0 if none
+pub const INPUT_SUNK: Symbol = symbol_short!("sunk");
+/// Defender's commitment domain (see `get_commitment_domain`)
+pub const INPUT_DOMAIN: Symbol = symbol_short!("domain");
+/// Nullifier of the answer, naming the cell answered of the defender's
+/// commitment (see `get_shot_nullifier`); each is accepted once
+pub const INPUT_NULLIFIER: Symbol = symbol_short!("nullifier");
+
+/// Public inputs of the shot circuit in `circuits/`, in its order.
+pub const CIRCUIT_LAYOUT: [Symbol; 11] = [
+    INPUT_SESSION,
+    INPUT_TURN,
+    INPUT_CONTRACT,
+    INPUT_COMMITMENT,
+    INPUT_SHOT_X,
+    INPUT_SHOT_Y,
+    INPUT_RESPONSE,
+    INPUT_HITS,
+    INPUT_SUNK,
+    INPUT_DOMAIN,
+    INPUT_NULLIFIER,
+];
+
+/// Every public input of one shot response, as field elements or the
+/// integers they encode.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShotInputs {
+    pub session_id: u32,
+    pub turn: u32,
+    /// See `contract`
+    pub contract: Fr,
+    /// See `commitment`
+    pub commitment: Fr,
+    pub shot_x: u32,
+    pub shot_y: u32,
+    pub response: u32,
+    /// See `hits`
+    pub hits: Fr,
+    pub sunk: u32,
+    /// See `domain`
+    pub domain: Fr,
+    /// See `nullifier`
+    pub nullifier: Fr,
+}
+
+impl ShotInputs {
+    /// The value of the input `name`; `None` if it names none.
+    pub fn value(&self, name: &Symbol) -> Option<Fr> {
+        let name = name.clone();
+        Some(if name == INPUT_SESSION {
+            Fr::from_u64(self.session_id as u64)
+        } else if name == INPUT_TURN {
+            Fr::from_u64(self.turn as u64)
+        } else if name == INPUT_CONTRACT {
+            self.contract
+        } else if name == INPUT_COMMITMENT {
+            self.commitment
+        } else if name == INPUT_SHOT_X {
+            Fr::from_u64(self.shot_x as u64)
+        } else if name == INPUT_SHOT_Y {
+            Fr::from_u64(self.shot_y as u64)
+        } else if name == INPUT_RESPONSE {
+            Fr::from_u64(self.response as u64)
+        } else if name == INPUT_HITS {
+            self.hits
+        } else if name == INPUT_SUNK {
+            Fr::from_u64(self.sunk as u64)
+        } else if name == INPUT_DOMAIN {
+            self.domain
+        } else if name == INPUT_NULLIFIER {
+            self.nullifier
+        } else {
+            return None;
+        })
+    }
+
+    /// The inputs in the order `layout` names them. Entries past the
+    /// layout's length are zero; `None` if the layout names an unknown
+    /// input or more than `MAX_PUBLIC_INPUTS`.
+    pub fn pack(&self, layout: &Vec<Symbol>) -> Option<[Fr; MAX_PUBLIC_INPUTS as usize]> {
+        if layout.len() > MAX_PUBLIC_INPUTS {
+            return None;
+        }
+        let mut inputs = [Fr::zero(); MAX_PUBLIC_INPUTS as usize];
+        for (input, name) in inputs.iter_mut().zip(layout.iter()) {
+            *input = self.value(&name)?;
+        }
+        Some(inputs)
+    }
+
+    /// As `pack`, each input as 32 big-endian bytes, the encoding
+    /// `check_proof` and the verifier take.
+    pub fn pack_bytes(&self, env: &Env, layout: &Vec<Symbol>) -> Option<Vec<BytesN<32>>> {
+        let inputs = self.pack(layout)?;
+        let mut encoded = Vec::new(env);
+        for input in &inputs[..layout.len() as usize] {
+            encoded.push_back(BytesN::from_array(env, &input.to_be_bytes()));
+        }
+        Some(encoded)
+    }
+}
+
+/// `CIRCUIT_LAYOUT` as a layout.
+pub fn circuit_layout(env: &Env) -> Vec<Symbol> {
+    Vec::from_array(env, CIRCUIT_LAYOUT)
+}
+
+/// The `INPUT_COMMITMENT` value of `commitment`. Poseidon2 commitments are
+/// field elements already (`None` if not); SHA-256 and Keccak digests enter
+/// reduced modulo the field, as Noir's `Field` would take them.
+pub fn commitment(scheme: CommitmentScheme, commitment: &BytesN<32>) -> Option<Fr> {
+    match scheme {
+        CommitmentScheme::Poseidon2 => Fr::from_be_bytes(&commitment.to_array()),
+        CommitmentScheme::Sha256 | CommitmentScheme::Keccak => Some(Fr::from_be_bytes_reduced(&commitment.to_array())),
+    }
+}
+
+/// The `INPUT_CONTRACT` value for `contract`: sha256 of its address XDR,
+/// reduced into the field.
+pub fn contract(env: &Env, contract: &Address) -> Fr {
+    Fr::from_be_bytes_reduced(&env.crypto().sha256(&contract.clone().to_xdr(env)).to_array())
+}
+
+/// The `INPUT_DOMAIN` value of `player` in session `session_id` of
+/// `contract`: sha256 over the contract's address XDR, the session id
+/// (4 bytes, big-endian) and the player's address XDR, reduced into the
+/// field.
+pub fn domain(env: &Env, contract: &Address, session_id: u32, player: &Address) -> Fr {
+    let mut bytes = contract.clone().to_xdr(env);
+    bytes.extend_from_array(&session_id.to_be_bytes());
+    bytes.append(&player.clone().to_xdr(env));
+    Fr::from_be_bytes_reduced(&env.crypto().sha256(&bytes).to_array())
+}
+
+/// The `INPUT_HITS` value for the hit cells `cells`, as (x, y).
+pub fn hits(cells: impl IntoIterator<Item = (u32, u32)>) -> Fr {
+    let mut mask = [0u8; 32];
+    for (x, y) in cells {
+        let bit = x * 10 + y;
+        mask[31 - (bit / 8) as usize] |= 1 << (bit % 8);
+    }
+    Fr::from_be_bytes_reduced(&mask)
+}
+
+/// The `INPUT_NULLIFIER` value for the answer to cell (`x`, `y`) of the
+/// board committed to as `commitment`: H(commitment || x * 10 + y).
+/// Poseidon2 hashes the commitment and cell index as two field elements
+/// (`None` if the commitment is not one); byte hashes take the cell index
+/// as one byte, and the digest enters reduced into the field.
+pub fn nullifier(env: &Env, scheme: CommitmentScheme, commitment: &BytesN<32>, x: u32, y: u32) -> Option<Fr> {
+    let index = x * 10 + y;
+    if scheme == CommitmentScheme::Poseidon2 {
+        let inputs = [Fr::from_be_bytes(&commitment.to_array())?, Fr::from_u64(index as u64)];
+        return Some(poseidon2::hash(&inputs));
+    }
+    let mut bytes = Bytes::from(commitment);
+    bytes.push_back(index as u8);
+    Some(Fr::from_be_bytes_reduced(&BattleshipContract::digest(env, scheme, &bytes).to_array()))
+}
@@ -0,0 +1,94 @@
+// ============================================================================
+// Per-Game Storage Namespace
+// ============================================================================
+// Battleship's entries (`GameKey`) share each game's namespace with the
+// framework's `SessionKey`s; see `zk_game::storage`.
+
+use soroban_sdk::{Address, Vec};
+
+pub use zk_game::storage::{GameStore, SessionKey, StorageTier};
+use zk_game::session::SESSION_TTL;
+
+use crate::{CommitmentConfig, CommitmentMode, GameKey, GameSummary};
+
+/// Enumerate every battleship key a game may have written.
+pub fn game_keys(game: &GameStore) -> Vec<GameKey> {
+    let mut keys = Vec::from_array(
+        game.env(),
+        [
+            GameKey::PendingShot, GameKey::Summary, GameKey::Circuit, GameKey::Commitment, GameKey::Replay,
+            GameKey::Transcript, GameKey::Fleet, GameKey::ShipCells, GameKey::Participants,
+            GameKey::Mover, GameKey::DrawOffer, GameKey::Practice, GameKey::Puzzle, GameKey::CommitDeadline,
+            GameKey::LastActive,
+        ],
+    );
+    if let Some(pending) = game.get(GameKey::PendingShot) {
+        keys.push_back(GameKey::ProofUpload(pending));
+    }
+    let optimistic = game.get::<CommitmentConfig>(GameKey::Commitment)
+        .is_some_and(|config| config.mode == CommitmentMode::Optimistic);
+    if optimistic {
+        let summary: Option<GameSummary> = game.get(GameKey::Summary);
+        for turn in 1..=summary.map_or(0, |summary| summary.turn) {
+            keys.push_back(GameKey::Response(turn));
+        }
+    }
+    for player in players(game).iter() {
+        keys.push_back(GameKey::PlayerState(player.clone()));
+        keys.push_back(GameKey::Audit(player.clone()));
+        keys.push_back(GameKey::Bombardment(player.clone()));
+        keys.push_back(GameKey::Mines(player.clone()));
+        keys.push_back(GameKey::SkipTurn(player.clone()));
+        keys.push_back(GameKey::Eliminated(player.clone()));
+        keys.push_back(GameKey::Rematch(player.clone()));
+        keys.push_back(GameKey::ResetRequest(player.clone()));
+        keys.push_back(GameKey::CancelRequest(player.clone()));
+        keys.push_back(GameKey::SonarScans(player.clone()));
+        keys.push_back(GameKey::ShotCommit(player.clone()));
+        keys.push_back(GameKey::RoundShot(player.clone()));
+        keys.push_back(GameKey::Stats(player));
+    }
+    keys
+}
+
+/// Every player seated in a game: the participants of a free-for-all game,
+/// otherwise both seats.
+pub fn players(game: &GameStore) -> Vec<Address> {
+    game.get(GameKey::Participants).unwrap_or_else(|| {
+        let mut seats = Vec::new(game.env());
+        for seat in [SessionKey::Player1, SessionKey::Player2] {
+            if let Some(player) = game.get::<Address>(seat) {
+                seats.push_back(player);
+            }
+        }
+        seats
+    })
+}
+
+/// Battleship keys written when a game is set up and rarely after: its
+/// configuration, fleets and the seats' standing state.
+pub fn live_keys(game: &GameStore) -> Vec<GameKey> {
+    let mut keys = Vec::from_array(
+        game.env(),
+        [
+            GameKey::Summary, GameKey::Circuit, GameKey::Commitment, GameKey::Fleet, GameKey::ShipCells,
+            GameKey::Participants, GameKey::Practice, GameKey::Puzzle, GameKey::CommitDeadline,
+        ],
+    );
+    for player in players(game).iter() {
+        keys.push_back(GameKey::PlayerState(player.clone()));
+        keys.push_back(GameKey::Mines(player));
+    }
+    keys
+}
+
+/// Remove every entry belonging to a game.
+pub fn clear(game: &GameStore) {
+    game.clear(&game_keys(game));
+}
+
+/// Keep the entries a live game wrote at set-up alive for `SESSION_TTL`
+/// from now; everything else is kept alive by being written.
+pub fn extend_ttl(game: &GameStore) {
+    game.extend_live(&live_keys(game), SESSION_TTL);
+}
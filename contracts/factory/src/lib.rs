@@ -0,0 +1,393 @@
+#![no_std]
+
+// ============================================================================
+// Game Factory Contract
+// ============================================================================
+// Deploys a fresh battleship contract for every match, so one match's storage,
+// keys and admin actions can never touch another's. The admin, named when the
+// factory is deployed, uploads the battleship wasm and records its hash here
+// with the verifier and circuits each game should start with; after that any
+// two players can create a game between them, which deploys the instance,
+// configures it, initialises the session and hands the instance's admin role
+// to the factory admin.
+//
+// Every game created is indexed by session id, in creation order and by
+// player, so frontends can discover games without an indexer.
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, vec, Address, BytesN, Env,
+    IntoVal, Symbol, Val, Vec,
+};
+use zk_game::admin;
+use zk_game::session::GamePhase;
+use zk_game::verifier::CircuitVersion;
+
+/// Most games one page of `list_games`, `list_active_games` or
+/// `get_games_for_player` covers.
+pub const MAX_PAGE: u32 = 50;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum FactoryError {
+    /// No deployment configured yet
+    NotConfigured = 1,
+    /// A game was already created for this session
+    SessionTaken = 2,
+    /// No admin has been set for this factory
+    AdminNotSet = 3,
+}
+
+impl From<zk_game::Error> for FactoryError {
+    fn from(err: zk_game::Error) -> Self {
+        match err {
+            zk_game::Error::AdminNotSet => FactoryError::AdminNotSet,
+            _ => FactoryError::NotConfigured,
+        }
+    }
+}
+
+/// How each new battleship instance is deployed and configured.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeploymentConfig {
+    /// Hash of the uploaded battleship wasm
+    pub wasm_hash: BytesN<32>,
+    /// Game hub every game reports to
+    pub hub: Address,
+    /// Shared proof verifier, if games verify on-chain
+    pub verifier: Option<Address>,
+    /// Shot circuit versions registered on each instance
+    pub circuits: Vec<(Symbol, CircuitVersion)>,
+    /// Verification key ids set on each instance, by circuit
+    pub keys: Vec<(Symbol, BytesN<32>)>,
+}
+
+/// Storage keys.
+#[contracttype]
+#[derive(Clone)]
+pub enum FactoryKey {
+    /// Deployment configuration (instance storage)
+    Deployment,
+    /// Games created so far (instance storage)
+    GameCount,
+    /// Game contract, by session id (persistent storage)
+    Game(u32),
+    /// Session id of the nth game created (persistent storage)
+    GameAt(u32),
+    /// Games a player was created into (persistent storage)
+    PlayerGameCount(Address),
+    /// Session id of a player's nth game (persistent storage)
+    PlayerGameAt(Address, u32),
+}
+
+#[contract]
+pub struct FactoryContract;
+
+#[contractimpl]
+impl FactoryContract {
+    // ========================================================================
+    // Administration
+    // ========================================================================
+
+    /// Deploy with `admin` as the factory admin.
+    pub fn __constructor(env: Env, admin: Address) {
+        admin::init_admin(&env, &admin);
+    }
+
+    /// Hand the factory admin role to `new_admin`. Current admin only.
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), FactoryError> {
+        Ok(admin::set_admin(&env, &new_admin)?)
+    }
+
+    /// Set how new games are deployed and configured. Games already created
+    /// keep the configuration they started with. Admin only.
+    pub fn configure(env: Env, config: DeploymentConfig) -> Result<(), FactoryError> {
+        admin::require_admin(&env)?;
+        env.storage().instance().set(&FactoryKey::Deployment, &config);
+        Ok(())
+    }
+
+    /// Get the configuration new games are deployed with, if set.
+    pub fn get_config(env: Env) -> Option<DeploymentConfig> {
+        env.storage().instance().get(&FactoryKey::Deployment)
+    }
+
+    // ========================================================================
+    // Game Creation
+    // ========================================================================
+
+    /// Deploy a battleship contract for `session_id` and initialise the game
+    /// on it between `player1` and `player2`, as its `initialize` would with
+    /// `circuit_id` and `commitment` (a battleship `CommitmentConfig`).
+    /// Both players authorise the session. Returns the new contract.
+    pub fn create_game(
+        env: Env,
+        session_id: u32,
+        player1: Address,
+        player2: Address,
+        circuit_id: Symbol,
+        commitment: Val,
+    ) -> Result<Address, FactoryError> {
+        player1.require_auth();
+        player2.require_auth();
+        let config: DeploymentConfig = env.storage().instance().get(&FactoryKey::Deployment)
+            .ok_or(FactoryError::NotConfigured)?;
+        let owner: Address = env.storage().instance().get(&zk_game::DataKey::Admin)
+            .ok_or(FactoryError::AdminNotSet)?;
+        let key = FactoryKey::Game(session_id);
+        if env.storage().persistent().has(&key) {
+            return Err(FactoryError::SessionTaken);
+        }
+
+        let mut salt = [0u8; 32];
+        salt[28..].copy_from_slice(&session_id.to_be_bytes());
+        let factory = env.current_contract_address();
+        let game = env.deployer()
+            .with_current_contract(BytesN::from_array(&env, &salt))
+            .deploy_v2(config.wasm_hash, (factory,));
+
+        let _: Val = env.invoke_contract(&game, &Symbol::new(&env, "set_verifier"), vec![&env, config.verifier.into_val(&env)]);
+        for (circuit, version) in config.circuits.iter() {
+            let _: Val = env.invoke_contract(
+                &game,
+                &Symbol::new(&env, "register_circuit"),
+                vec![&env, circuit.into_val(&env), version.into_val(&env)],
+            );
+        }
+        for (circuit, vk_id) in config.keys.iter() {
+            let _: Val = env.invoke_contract(
+                &game,
+                &Symbol::new(&env, "set_verification_key"),
+                vec![&env, circuit.into_val(&env), vk_id.into_val(&env)],
+            );
+        }
+        let _: Val = env.invoke_contract(
+            &game,
+            &Symbol::new(&env, "initialize"),
+            vec![
+                &env,
+                config.hub.into_val(&env),
+                session_id.into_val(&env),
+                player1.into_val(&env),
+                player2.into_val(&env),
+                circuit_id.into_val(&env),
+                commitment,
+            ],
+        );
+        let _: Val = env.invoke_contract(&game, &Symbol::new(&env, "set_admin"), vec![&env, owner.into_val(&env)]);
+
+        let count: u32 = env.storage().instance().get(&FactoryKey::GameCount).unwrap_or(0);
+        env.storage().persistent().set(&key, &game);
+        extend_ttl(&env, &key);
+        let at = FactoryKey::GameAt(count);
+        env.storage().persistent().set(&at, &session_id);
+        extend_ttl(&env, &at);
+        env.storage().instance().set(&FactoryKey::GameCount, &(count + 1));
+        for player in [&player1, &player2] {
+            let seated = Self::get_game_count_for_player(env.clone(), player.clone());
+            let at = FactoryKey::PlayerGameAt(player.clone(), seated);
+            env.storage().persistent().set(&at, &session_id);
+            extend_ttl(&env, &at);
+            let key = FactoryKey::PlayerGameCount(player.clone());
+            env.storage().persistent().set(&key, &(seated + 1));
+            extend_ttl(&env, &key);
+        }
+
+        env.events().publish((symbol_short!("created"), session_id), game.clone());
+        Ok(game)
+    }
+
+    // ========================================================================
+    // Views
+    // ========================================================================
+
+    /// Get the contract hosting `session_id`, if this factory created it.
+    pub fn get_game(env: Env, session_id: u32) -> Option<Address> {
+        env.storage().persistent().get(&FactoryKey::Game(session_id))
+    }
+
+    /// Get how many games this factory has created.
+    pub fn get_game_count(env: Env) -> u32 {
+        env.storage().instance().get(&FactoryKey::GameCount).unwrap_or(0)
+    }
+
+    /// List games in creation order, as `(session_id, contract)`, starting
+    /// at `offset` and returning at most `limit` (capped at `MAX_PAGE`).
+    pub fn list_games(env: Env, offset: u32, limit: u32) -> Vec<(u32, Address)> {
+        let count = Self::get_game_count(env.clone());
+        let end = count.min(offset.saturating_add(limit.min(MAX_PAGE)));
+        let mut games = vec![&env];
+        for index in offset..end {
+            if let Some(game) = game_at(&env, index) {
+                games.push_back(game);
+            }
+        }
+        games
+    }
+
+    /// List games not yet finished, in creation order, as
+    /// `(session_id, contract)`. Checks the `limit` games (capped at
+    /// `MAX_PAGE`) created from the `offset`th on, as `list_games` returns
+    /// them, and keeps the unfinished ones, so a page can come back short or
+    /// empty before the end; the next page starts at `offset + limit`. Games
+    /// whose contract no longer answers (e.g. its storage expired) are
+    /// skipped.
+    pub fn list_active_games(env: Env, offset: u32, limit: u32) -> Vec<(u32, Address)> {
+        let count = Self::get_game_count(env.clone());
+        let end = count.min(offset.saturating_add(limit.min(MAX_PAGE)));
+        let phase = Symbol::new(&env, "get_phase");
+        let mut games = vec![&env];
+        for index in offset..end {
+            if let Some((session_id, game)) = game_at(&env, index) {
+                let active = matches!(
+                    env.try_invoke_contract::<GamePhase, soroban_sdk::Error>(
+                        &game,
+                        &phase,
+                        vec![&env, session_id.into_val(&env)],
+                    ),
+                    Ok(Ok(current)) if current != GamePhase::Finished
+                );
+                if active {
+                    games.push_back((session_id, game));
+                }
+            }
+        }
+        games
+    }
+
+    /// Get how many games `player` was created into.
+    pub fn get_game_count_for_player(env: Env, player: Address) -> u32 {
+        env.storage().persistent().get(&FactoryKey::PlayerGameCount(player)).unwrap_or(0)
+    }
+
+    /// Get the session ids of the games `player` was created into, oldest
+    /// first, starting at their `offset`th and returning at most `limit`
+    /// (capped at `MAX_PAGE`).
+    pub fn get_games_for_player(env: Env, player: Address, offset: u32, limit: u32) -> Vec<u32> {
+        let count = Self::get_game_count_for_player(env.clone(), player.clone());
+        let end = count.min(offset.saturating_add(limit.min(MAX_PAGE)));
+        let mut games = vec![&env];
+        for index in offset..end {
+            if let Some(session_id) = env.storage().persistent().get(&FactoryKey::PlayerGameAt(player.clone(), index)) {
+                games.push_back(session_id);
+            }
+        }
+        games
+    }
+}
+
+/// The `index`th game created, as `(session_id, contract)`.
+fn game_at(env: &Env, index: u32) -> Option<(u32, Address)> {
+    let session_id: u32 = env.storage().persistent().get(&FactoryKey::GameAt(index))?;
+    let game = env.storage().persistent().get(&FactoryKey::Game(session_id))?;
+    Some((session_id, game))
+}
+
+/// Keep a key alive for approximately 30 days past its last write.
+fn extend_ttl(env: &Env, key: &FactoryKey) {
+    let thirty_days: u32 = 30 * 24 * 60 * 60; // ~2,592,000 ledgers
+    env.storage().persistent().extend_ttl(key, thirty_days, thirty_days);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    // Built with `cargo build --release --target wasm32-unknown-unknown -p battleship`
+    mod battleship {
+        soroban_sdk::contractimport!(file = "../target/wasm32-unknown-unknown/release/battleship.wasm");
+    }
+
+    fn config(env: &Env) -> DeploymentConfig {
+        DeploymentConfig {
+            wasm_hash: BytesN::from_array(env, &[7u8; 32]),
+            hub: Address::generate(env),
+            verifier: None,
+            circuits: vec![env],
+            keys: vec![env],
+        }
+    }
+
+    #[test]
+    fn test_configure_requires_admin() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let client = FactoryContractClient::new(&env, &env.register(FactoryContract, (admin.clone(),)));
+
+        let config = config(&env);
+        client.configure(&config);
+        assert_eq!(env.auths()[0].0, admin);
+        assert_eq!(client.get_config(), Some(config));
+    }
+
+    #[test]
+    fn test_create_game_needs_configuration() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = FactoryContractClient::new(&env, &env.register(FactoryContract, (Address::generate(&env),)));
+        let (p1, p2) = (Address::generate(&env), Address::generate(&env));
+        let commitment: Val = ().into_val(&env);
+
+        let result = client.try_create_game(&1, &p1, &p2, &symbol_short!("shot"), &commitment);
+        assert_eq!(result, Err(Ok(FactoryError::NotConfigured)));
+        assert_eq!(client.get_game_count(), 0);
+        assert_eq!(client.get_game(&1), None);
+        assert!(client.list_games(&0, &10).is_empty());
+        assert!(client.list_active_games(&0, &10).is_empty());
+        assert!(client.get_games_for_player(&p1, &0, &10).is_empty());
+    }
+    #[test]
+    fn test_create_game_deploys_instance() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let client = FactoryContractClient::new(&env, &env.register(FactoryContract, (admin,)));
+        let wasm_hash = env.deployer().upload_contract_wasm(battleship::WASM);
+        client.configure(&DeploymentConfig { wasm_hash, ..config(&env) });
+
+        // A Merkle game needs no verifier or circuits
+        let (p1, p2) = (Address::generate(&env), Address::generate(&env));
+        let commitment = battleship::CommitmentConfig {
+            mode: battleship::CommitmentMode::Merkle,
+            scheme: battleship::CommitmentScheme::Sha256,
+            board_size: 10,
+            turn_timeout: 0,
+            time_budget: 0,
+            ammunition: 0,
+            mines: 0,
+            rounds: 0,
+            max_turns: 0,
+            coin_flip: false,
+            simultaneous: false,
+            sealed_shots: false,
+            hit_again: false,
+            storage: battleship::StorageTier::Temporary,
+            commit_window: 0,
+            proof_window: 0,
+            missing_proof: battleship::MissingProof::Forfeit,
+            reveal_window: 100,
+        };
+        let commitment: Val = commitment.into_val(&env);
+        let game = client.create_game(&1, &p1, &p2, &symbol_short!("shot"), &commitment);
+        for player in [&p1, &p2] {
+            assert!(env.auths().iter().any(|(signer, _)| signer == player));
+        }
+
+        // The session lives on its own instance
+        let instance = battleship::Client::new(&env, &game);
+        assert_eq!(instance.get_phase(&1), battleship::GamePhase::WaitingForCommits);
+        assert_eq!(client.get_game(&1), Some(game.clone()));
+        assert_eq!(client.list_games(&0, &10), vec![&env, (1, game.clone())]);
+        assert_eq!(client.list_active_games(&0, &10), vec![&env, (1, game.clone())]);
+        assert_eq!(client.get_games_for_player(&p2, &0, &10), vec![&env, 1]);
+        assert_eq!(client.get_game_count_for_player(&p2), 1);
+        assert!(client.get_games_for_player(&p2, &1, &10).is_empty());
+
+        // Each session id gets one contract
+        let result = client.try_create_game(&1, &p1, &p2, &symbol_short!("shot"), &commitment);
+        assert_eq!(result, Err(Ok(FactoryError::SessionTaken)));
+    }
+}
@@ -0,0 +1,284 @@
+#![no_std]
+
+// ============================================================================
+// Proof Verifier Contract
+// ============================================================================
+// One deployment checks proofs for every game that points at it. Keys are
+// registered by anyone and named by the hash of their contents, so a key
+// never changes once a game relies on it and no admin is needed; games keep
+// their own record of which key each circuit uses (see `zk_game::verifier`).
+//
+// Verification itself is the framework's: `zk_game::proof` dispatches on the
+// proof system, to Groth16 over BN254 (the default `bn254-verifier`
+// feature) or over BLS12-381, whose keys are registered separately. Local sandboxes can build with `mock-verifier`
+// instead to accept placeholder proofs; release builds refuse to compile
+// without the real verifier, so the mock cannot be deployed by accident.
+
+#[cfg(all(not(debug_assertions), not(feature = "bn254-verifier")))]
+compile_error!("release builds of the verifier need the `bn254-verifier` feature; `mock-verifier` accepts every proof");
+
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, BytesN, Env, Vec};
+use zk_game::bn254::Fr;
+use zk_game::groth16::{self, VerificationKey};
+use zk_game::groth16_bls::{self, BlsVerificationKey};
+use zk_game::proof::{self, Proof};
+use zk_game::verifier::{self as registry, Verifier, VerifierError, MAX_PUBLIC_INPUTS};
+
+/// Storage keys.
+#[contracttype]
+#[derive(Clone)]
+pub enum VerifierKey {
+    /// Registered verification key, by id (persistent storage)
+    Key(BytesN<32>),
+    /// Registered BLS12-381 verification key, by id (persistent storage)
+    BlsKey(BytesN<32>),
+}
+
+#[contract]
+pub struct VerifierContract;
+
+#[contractimpl]
+impl Verifier for VerifierContract {
+    fn register_key(env: Env, vk: VerificationKey) -> Result<BytesN<32>, VerifierError> {
+        if !groth16::is_well_formed(&vk) {
+            return Err(VerifierError::InvalidVerificationKey);
+        }
+        let vk_id = registry::vk_id(&env, &vk);
+        let key = VerifierKey::Key(vk_id.clone());
+        if !env.storage().persistent().has(&key) {
+            env.storage().persistent().set(&key, &vk);
+            env.events().publish((symbol_short!("vk_reg"),), vk_id.clone());
+        }
+        extend_ttl(&env, &key);
+        Ok(vk_id)
+    }
+
+    fn get_key(env: Env, vk_id: BytesN<32>) -> Option<VerificationKey> {
+        env.storage().persistent().get(&VerifierKey::Key(vk_id))
+    }
+
+    fn register_bls_key(env: Env, vk: BlsVerificationKey) -> Result<BytesN<32>, VerifierError> {
+        if !groth16_bls::is_well_formed(&env, &vk) {
+            return Err(VerifierError::InvalidVerificationKey);
+        }
+        let vk_id = registry::bls_vk_id(&env, &vk);
+        let key = VerifierKey::BlsKey(vk_id.clone());
+        if !env.storage().persistent().has(&key) {
+            env.storage().persistent().set(&key, &vk);
+            env.events().publish((symbol_short!("vk_reg"),), vk_id.clone());
+        }
+        extend_ttl(&env, &key);
+        Ok(vk_id)
+    }
+
+    fn get_bls_key(env: Env, vk_id: BytesN<32>) -> Option<BlsVerificationKey> {
+        env.storage().persistent().get(&VerifierKey::BlsKey(vk_id))
+    }
+
+    fn verify(
+        env: Env,
+        vk_id: BytesN<32>,
+        public_inputs: Vec<BytesN<32>>,
+        proof: Proof,
+    ) -> Result<bool, VerifierError> {
+        if public_inputs.len() > MAX_PUBLIC_INPUTS {
+            return Err(VerifierError::InvalidPublicInput);
+        }
+        let mut inputs = [Fr::zero(); MAX_PUBLIC_INPUTS as usize];
+        for (slot, input) in inputs.iter_mut().zip(public_inputs.iter()) {
+            *slot = Fr::from_be_bytes(&input.to_array()).ok_or(VerifierError::InvalidPublicInput)?;
+        }
+
+        let inputs = &inputs[..public_inputs.len() as usize];
+
+        let result = if proof::is_bls12_381(proof.system) {
+            let key = VerifierKey::BlsKey(vk_id);
+            let vk: BlsVerificationKey = env.storage().persistent().get(&key)
+                .ok_or(VerifierError::KeyNotFound)?;
+            extend_ttl(&env, &key);
+            proof::verify_bls12_381(&env, &vk, &proof, inputs)
+        } else {
+            let key = VerifierKey::Key(vk_id);
+            let vk: VerificationKey = env.storage().persistent().get(&key)
+                .ok_or(VerifierError::KeyNotFound)?;
+            extend_ttl(&env, &key);
+            proof::verify(&vk, &proof, inputs)
+        };
+        match result {
+            Ok(valid) => Ok(valid),
+            Err(zk_game::Error::ProofTooLarge) => Err(VerifierError::ProofTooLarge),
+            Err(_) => Err(VerifierError::UnsupportedProofSystem),
+        }
+    }
+}
+
+// ============================================================================
+// Benchmark
+// ============================================================================
+// Built with the `bench` feature (and in tests), to measure what one
+// verification costs against Soroban's resource limits while tuning circuit
+// size and proof system. The pairing runs in the contract, so only the wasm
+// build's budget is meaningful: simulate the call, or invoke it with the CLI's
+// `--cost`, and divide by `iterations`.
+
+#[cfg(any(test, feature = "bench"))]
+#[contractimpl]
+impl VerifierContract {
+    /// Run the four-pair BN254 pairing check a Groth16 verification ends in
+    /// `iterations` times, over fixed points for which it holds, logging
+    /// each run. Returns how many held.
+    pub fn bench_verify(env: Env, iterations: u32) -> u32 {
+        use soroban_sdk::log;
+        use zk_game::bn254::{self, G1Affine, G2Affine};
+
+        let (g1, g2) = (G1Affine::generator(), G2Affine::generator());
+        let pairs = [(-g1, g2), (g1, g2), (-g1, g2), (g1, g2)];
+        let mut held = 0;
+        for run in 1..=iterations {
+            if bn254::pairing_check(&pairs) {
+                held += 1;
+            }
+            log!(&env, "bench_verify: pairing check {} of {}", run, iterations);
+        }
+        held
+    }
+}
+
+/// Keep a key alive for approximately 30 days past its last use.
+fn extend_ttl(env: &Env, key: &VerifierKey) {
+    let thirty_days: u32 = 30 * 24 * 60 * 60; // ~2,592,000 ledgers
+    env.storage().persistent().extend_ttl(key, thirty_days, thirty_days);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::Bytes;
+    use zk_game::groth16::testutils::Trapdoor;
+    use zk_game::groth16_bls::testutils::BlsTrapdoor;
+    use zk_game::proof::ProofSystem;
+    use zk_game::verifier::VerifierClient;
+
+    fn inputs(env: &Env, values: &[u64]) -> (Vec<BytesN<32>>, [Fr; 2]) {
+        let mut encoded = Vec::new(env);
+        let mut fields = [Fr::zero(); 2];
+        for (field, value) in fields.iter_mut().zip(values) {
+            *field = Fr::from_u64(*value);
+            encoded.push_back(BytesN::from_array(env, &field.to_be_bytes()));
+        }
+        (encoded, fields)
+    }
+
+    #[test]
+    #[cfg(feature = "bn254-verifier")]
+    fn test_register_and_verify() {
+        let env = Env::default();
+        let client = VerifierClient::new(&env, &env.register(VerifierContract, ()));
+        let trapdoor = Trapdoor::new(2);
+        let vk = trapdoor.verification_key(&env);
+
+        // Keys are content-addressed: registering twice yields one id
+        let vk_id = client.register_key(&vk);
+        assert_eq!(client.register_key(&vk), vk_id);
+        assert_eq!(client.get_key(&vk_id), Some(vk.clone()));
+        let mut broken = vk.clone();
+        broken.ic = Vec::new(&env);
+        assert_eq!(client.try_register_key(&broken), Err(Ok(VerifierError::InvalidVerificationKey)));
+
+        let (encoded, fields) = inputs(&env, &[3, 4]);
+        let proof = Proof::groth16(&trapdoor.prove(&env, &fields));
+        assert!(client.verify(&vk_id, &encoded, &proof));
+        let (other, _) = inputs(&env, &[3, 5]);
+        assert!(!client.verify(&vk_id, &other, &proof));
+
+        let unknown = BytesN::from_array(&env, &[9u8; 32]);
+        assert_eq!(client.try_verify(&unknown, &encoded, &proof), Err(Ok(VerifierError::KeyNotFound)));
+        let mut out_of_field = encoded.clone();
+        out_of_field.set(0, BytesN::from_array(&env, &[0xff; 32]));
+        assert_eq!(client.try_verify(&vk_id, &out_of_field, &proof), Err(Ok(VerifierError::InvalidPublicInput)));
+
+        let honk = Proof { system: ProofSystem::UltraHonk, bytes: Bytes::new(&env) };
+        assert_eq!(client.try_verify(&vk_id, &encoded, &honk), Err(Ok(VerifierError::UnsupportedProofSystem)));
+    }
+
+    #[test]
+    #[cfg(not(feature = "mock-verifier"))]
+    fn test_bls12_381_keys_and_proofs() {
+        let env = Env::default();
+        let client = VerifierClient::new(&env, &env.register(VerifierContract, ()));
+        let trapdoor = BlsTrapdoor::new(2);
+        let vk = trapdoor.verification_key(&env);
+
+        let vk_id = client.register_bls_key(&vk);
+        assert_eq!(client.get_bls_key(&vk_id), Some(vk.clone()));
+        assert_eq!(client.get_key(&vk_id), None);
+        let mut broken = vk.clone();
+        broken.ic = Vec::new(&env);
+        assert_eq!(client.try_register_bls_key(&broken), Err(Ok(VerifierError::InvalidVerificationKey)));
+
+        // The proof's system picks the kind of key its id names
+        let (encoded, fields) = inputs(&env, &[3, 4]);
+        let proof = Proof::groth16_bls12_381(&trapdoor.prove(&env, &fields));
+        assert!(client.verify(&vk_id, &encoded, &proof));
+        let (other, _) = inputs(&env, &[3, 5]);
+        assert!(!client.verify(&vk_id, &other, &proof));
+        let truncated = Proof { bytes: proof.bytes.slice(..256), ..proof.clone() };
+        assert!(!client.verify(&vk_id, &encoded, &truncated));
+        let bn254 = Proof { system: ProofSystem::Groth16, ..proof.clone() };
+        assert_eq!(client.try_verify(&vk_id, &encoded, &bn254), Err(Ok(VerifierError::KeyNotFound)));
+    }
+
+    // Built with `cargo build --release --target wasm32-unknown-unknown -p verifier`
+    mod wasm {
+        soroban_sdk::contractimport!(file = "../target/wasm32-unknown-unknown/release/verifier.wasm");
+    }
+
+    #[test]
+    #[cfg(feature = "bn254-verifier")]
+    fn test_verify_fits_cpu_budget() {
+        // Only wasm execution is metered, so run the release build
+        let env = Env::default();
+        env.cost_estimate().budget().reset_unlimited();
+        let client = VerifierClient::new(&env, &env.register(wasm::WASM, ()));
+        let trapdoor = Trapdoor::new(MAX_PUBLIC_INPUTS);
+        let vk_id = client.register_key(&trapdoor.verification_key(&env));
+        let mut encoded = Vec::new(&env);
+        let mut fields = [Fr::zero(); MAX_PUBLIC_INPUTS as usize];
+        for (i, field) in fields.iter_mut().enumerate() {
+            *field = Fr::from_u64(0xb0a7 + i as u64);
+            encoded.push_back(BytesN::from_array(&env, &field.to_be_bytes()));
+        }
+        let proof = Proof::groth16(&trapdoor.prove(&env, &fields));
+
+        // The most inputs a proof can have, within a transaction's default
+        // limit of 100M CPU instructions
+        env.cost_estimate().budget().reset_default();
+        assert!(client.verify(&vk_id, &encoded, &proof));
+        assert!(env.cost_estimate().budget().cpu_instruction_cost() <= 100_000_000);
+    }
+
+    #[test]
+    fn test_bench_verify() {
+        let env = Env::default();
+        let client = VerifierContractClient::new(&env, &env.register(VerifierContract, ()));
+        assert_eq!(client.bench_verify(&2), 2);
+        assert_eq!(client.bench_verify(&0), 0);
+    }
+
+    #[test]
+    #[cfg(all(feature = "mock-verifier", not(feature = "bn254-verifier")))]
+    fn test_mock_accepts_placeholders() {
+        let env = Env::default();
+        let client = VerifierClient::new(&env, &env.register(VerifierContract, ()));
+        let vk_id = client.register_key(&Trapdoor::new(2).verification_key(&env));
+
+        // Keys and inputs are still checked; the proof is not
+        let (encoded, _) = inputs(&env, &[3, 4]);
+        let placeholder = Proof::groth16(&BytesN::from_array(&env, &[0u8; 256]));
+        assert!(client.verify(&vk_id, &encoded, &placeholder));
+        let honk = Proof { system: ProofSystem::UltraHonk, bytes: Bytes::new(&env) };
+        assert!(client.verify(&vk_id, &encoded, &honk));
+        let unknown = BytesN::from_array(&env, &[9u8; 32]);
+        assert_eq!(client.try_verify(&unknown, &encoded, &placeholder), Err(Ok(VerifierError::KeyNotFound)));
+    }
+}
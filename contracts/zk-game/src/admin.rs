@@ -0,0 +1,138 @@
+// ============================================================================
+// Deployment Administration
+// ============================================================================
+// One admin per deployment configures the services games share (randomness
+// beacon, price oracle, pause registry, profile registry). The deployer names
+// the admin in the contract's constructor, so the role is never up for grabs;
+// afterwards only the current admin can hand it over.
+//
+// A service is installed at once while none is configured. Replacing or
+// removing one goes through the same timelock as a verification key: the
+// change is proposed, and can be activated `KEY_UPGRADE_DELAY` ledgers
+// later. Open games do not hold it up: each game pins the verifier it was
+// created with (see `verifier::pin`), and takes what it needs from the
+// beacon and oracle once, recording it (the first mover, a wager's price at
+// lock-in). The pause and profile registries are meant to be read live.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::verifier::KEY_UPGRADE_DELAY;
+use crate::{DataKey, Error};
+
+/// A deployment service the admin configures.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Service {
+    /// Randomness beacon, see `randomness`
+    RandomnessBeacon,
+    /// Price oracle, see `oracle`
+    PriceOracle,
+    /// Pause registry, see `hub::is_paused`
+    PauseRegistry,
+    /// Profile registry, see `profiles`
+    ProfileRegistry,
+    /// Proof verifier, see `verifier`
+    Verifier,
+}
+
+impl Service {
+    fn key(self) -> DataKey {
+        match self {
+            Service::RandomnessBeacon => DataKey::RandomnessBeacon,
+            Service::PriceOracle => DataKey::PriceOracle,
+            Service::PauseRegistry => DataKey::PauseRegistry,
+            Service::ProfileRegistry => DataKey::ProfileRegistry,
+            Service::Verifier => DataKey::Verifier,
+        }
+    }
+}
+
+/// A proposed service change, awaiting its timelock.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ServiceChange {
+    /// Contract that will replace the service's, or `None` to unset it
+    pub contract: Option<Address>,
+    /// First ledger the change can be activated in
+    pub activates_at: u32,
+}
+
+/// Install the first admin. Call only from a contract constructor, which
+/// runs once, as part of the deployment itself.
+pub fn init_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::Admin, admin);
+}
+
+/// Hand the admin role to `new_admin`. Current admin only.
+pub fn set_admin(env: &Env, new_admin: &Address) -> Result<(), Error> {
+    require_admin(env)?;
+    env.storage().instance().set(&DataKey::Admin, new_admin);
+    Ok(())
+}
+
+/// The deployment admin, if one has been set.
+pub fn admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Admin)
+}
+
+/// Require the deployment admin's authorisation.
+pub fn require_admin(env: &Env) -> Result<Address, Error> {
+    let admin: Address = env.storage().instance().get(&DataKey::Admin)
+        .ok_or(Error::AdminNotSet)?;
+    admin.require_auth();
+    Ok(admin)
+}
+
+/// Point `service` at `contract`, if it has none yet. Admin only. A
+/// configured service is replaced with `propose_service` and
+/// `activate_service`; setting `None` while none is configured does nothing.
+pub fn set_service(env: &Env, service: Service, contract: Option<Address>) -> Result<(), Error> {
+    require_admin(env)?;
+    let Some(contract) = contract else {
+        return Ok(());
+    };
+    if self::service(env, service).is_some() {
+        return Err(Error::KeyAlreadySet);
+    }
+    env.storage().instance().set(&service.key(), &contract);
+    Ok(())
+}
+
+/// Propose `contract` to replace `service`, or `None` to unset it; it can
+/// be activated `KEY_UPGRADE_DELAY` ledgers from now. Admin only. A new
+/// proposal replaces the last one and restarts the delay.
+pub fn propose_service(env: &Env, service: Service, contract: Option<Address>) -> Result<ServiceChange, Error> {
+    require_admin(env)?;
+    let change = ServiceChange {
+        contract,
+        activates_at: env.ledger().sequence() + KEY_UPGRADE_DELAY,
+    };
+    env.storage().instance().set(&DataKey::ServiceChange(service), &change);
+    Ok(change)
+}
+
+/// Apply the change proposed for `service` once its delay has passed.
+/// Admin only. Returns the service's new contract.
+pub fn activate_service(env: &Env, service: Service) -> Result<Option<Address>, Error> {
+    require_admin(env)?;
+    let change = service_change(env, service).ok_or(Error::KeyUpgradeNotReady)?;
+    if env.ledger().sequence() < change.activates_at {
+        return Err(Error::KeyUpgradeNotReady);
+    }
+    match &change.contract {
+        Some(contract) => env.storage().instance().set(&service.key(), contract),
+        None => env.storage().instance().remove(&service.key()),
+    }
+    env.storage().instance().remove(&DataKey::ServiceChange(service));
+    Ok(change.contract)
+}
+
+/// The change proposed for `service`, if any.
+pub fn service_change(env: &Env, service: Service) -> Option<ServiceChange> {
+    env.storage().instance().get(&DataKey::ServiceChange(service))
+}
+
+/// Contract configured for `service`, if any.
+pub fn service(env: &Env, service: Service) -> Option<Address> {
+    env.storage().instance().get(&service.key())
+}
@@ -0,0 +1,80 @@
+// ============================================================================
+// Result Attestation
+// ============================================================================
+// A finished session yields a canonical attestation that bridges and relayers
+// can sign and carry to other chains (cross-chain tournaments, NFT claims).
+// The attested message is a fixed 136-byte layout that is trivial to decode
+// outside Soroban, all integers big-endian:
+//
+//   version (4) | network_id (32) | sha256(game_id xdr) (32)
+//   | session_id (4) | sha256(winner xdr) (32) | transcript_digest (32)
+//
+// `digest` is keccak256 of that message, ready for EVM `ecrecover` flows.
+
+use soroban_sdk::{contracttype, symbol_short, xdr::ToXdr, Address, Bytes, BytesN, Env};
+
+use crate::storage::{GameStore, SessionKey};
+
+/// Layout version of the attested message
+pub const ATTESTATION_VERSION: u32 = 1;
+
+/// Signable record of a session's result.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attestation {
+    pub version: u32,
+    /// Network passphrase hash of the chain the game ran on
+    pub network_id: BytesN<32>,
+    /// Game contract that hosted the session
+    pub game_id: Address,
+    pub session_id: u32,
+    pub winner: Address,
+    /// Game-defined digest of the full move transcript
+    pub transcript_digest: BytesN<32>,
+    /// Canonical encoding of the fields above
+    pub message: Bytes,
+    /// keccak256 of `message`
+    pub digest: BytesN<32>,
+}
+
+/// Record and publish the attestation of `game`'s result.
+pub fn attest(game: &GameStore, winner: &Address, transcript_digest: &BytesN<32>) -> Attestation {
+    let env = game.env();
+    let network_id = env.ledger().network_id();
+    let game_id = env.current_contract_address();
+
+    let mut message = Bytes::new(env);
+    message.extend_from_array(&ATTESTATION_VERSION.to_be_bytes());
+    message.extend_from_array(&network_id.to_array());
+    message.extend_from_array(&address_hash(env, &game_id).to_array());
+    message.extend_from_array(&game.session_id().to_be_bytes());
+    message.extend_from_array(&address_hash(env, winner).to_array());
+    message.extend_from_array(&transcript_digest.to_array());
+    let digest = env.crypto().keccak256(&message).to_bytes();
+
+    let attestation = Attestation {
+        version: ATTESTATION_VERSION,
+        network_id,
+        game_id,
+        session_id: game.session_id(),
+        winner: winner.clone(),
+        transcript_digest: transcript_digest.clone(),
+        message,
+        digest: digest.clone(),
+    };
+    game.set(SessionKey::Attestation, &attestation);
+    env.events().publish(
+        (symbol_short!("attest"), game.session_id()),
+        digest,
+    );
+    attestation
+}
+
+/// The attestation of `game`'s result, once it has finished.
+pub fn get(game: &GameStore) -> Option<Attestation> {
+    game.get(SessionKey::Attestation)
+}
+
+fn address_hash(env: &Env, address: &Address) -> BytesN<32> {
+    env.crypto().sha256(&address.clone().to_xdr(env)).to_bytes()
+}
@@ -0,0 +1,123 @@
+// ============================================================================
+// Cheating Bonds
+// ============================================================================
+// A deployment may require each player to post a small bond in a SEP-41
+// token when a game is created. The bond does not ride on the result: an
+// honest player takes it back after the game, while one proven to have
+// cheated forfeits it to their opponent. What counts as proof is the
+// game's to decide.
+//
+// Terms are copied into the session when bonds are posted, so changing the
+// deployment's bond never affects games already created.
+
+use soroban_sdk::{contracttype, symbol_short, token::TokenClient, Address, Env};
+
+use crate::storage::{GameStore, SessionKey};
+use crate::{identity, DataKey, Error};
+
+/// Bond each player posts when a game is created.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BondConfig {
+    /// SEP-41 token contract
+    pub token: Address,
+    /// Bond per player, in the token's smallest unit
+    pub amount: i128,
+}
+
+/// Set the deployment's bond, or `None` to stop requiring one.
+pub fn configure(env: &Env, config: Option<BondConfig>) -> Result<(), Error> {
+    match config {
+        Some(config) if config.amount <= 0 => Err(Error::InvalidWager),
+        Some(config) => {
+            env.storage().instance().set(&DataKey::Bond, &config);
+            Ok(())
+        }
+        None => {
+            env.storage().instance().remove(&DataKey::Bond);
+            Ok(())
+        }
+    }
+}
+
+/// The deployment's bond, if one is required.
+pub fn config(env: &Env) -> Option<BondConfig> {
+    env.storage().instance().get(&DataKey::Bond)
+}
+
+/// Take `player`'s bond into escrow, with a transfer they authorise, if the
+/// deployment requires one. The bond must arrive in full, so games with a
+/// bond need both players to sign their creation.
+pub fn post(game: &GameStore, player: &Address) -> Result<(), Error> {
+    let env = game.env();
+    let Some(config) = game.get::<BondConfig>(SessionKey::Bond).or_else(|| config(env)) else {
+        return Ok(());
+    };
+    let account = identity::account(game, player);
+    account.require_auth();
+    let token = TokenClient::new(env, &config.token);
+    let contract = env.current_contract_address();
+    let before = balance(&token, &contract);
+    if !token.try_transfer(&account, &contract, &config.amount).is_ok_and(|r| r.is_ok()) {
+        return Err(Error::TransferFailed);
+    }
+    if balance(&token, &contract) - before != config.amount {
+        return Err(Error::DepositShortfall);
+    }
+
+    game.set(SessionKey::Bond, &config);
+    game.set(SessionKey::Bonded(player.clone()), &config.amount);
+    env.events().publish((symbol_short!("bonded"),), (player.clone(), config.amount));
+    Ok(())
+}
+
+/// Bond `player` has in escrow for this game.
+pub fn posted(game: &GameStore, player: &Address) -> i128 {
+    game.get(SessionKey::Bonded(player.clone())).unwrap_or(0)
+}
+
+/// Pay `player`'s bond out of escrow to `to`: back to the player, or to
+/// their opponent when forfeited. Returns the amount paid.
+pub fn release(game: &GameStore, player: &Address, to: &Address) -> Result<i128, Error> {
+    let env = game.env();
+    let amount = posted(game, player);
+    let config: BondConfig = game.get(SessionKey::Bond).ok_or(Error::NothingToWithdraw)?;
+    if amount <= 0 {
+        return Err(Error::NothingToWithdraw);
+    }
+    let token = TokenClient::new(env, &config.token);
+    if !token.try_transfer(&env.current_contract_address(), &identity::account(game, to), &amount).is_ok_and(|r| r.is_ok()) {
+        return Err(Error::TransferFailed);
+    }
+    game.remove(SessionKey::Bonded(player.clone()));
+    env.events().publish((symbol_short!("unbonded"), player.clone()), (to.clone(), amount));
+    Ok(amount)
+}
+
+/// Pay `player`'s bond back to them less `percent` of it, which goes to
+/// `collector`. Returns the amount `collector` received.
+pub fn release_with_bounty(game: &GameStore, player: &Address, collector: &Address, percent: i128) -> Result<i128, Error> {
+    let env = game.env();
+    let amount = posted(game, player);
+    let config: BondConfig = game.get(SessionKey::Bond).ok_or(Error::NothingToWithdraw)?;
+    if amount <= 0 {
+        return Err(Error::NothingToWithdraw);
+    }
+    let bounty = amount * percent / 100;
+    let token = TokenClient::new(env, &config.token);
+    for (to, share) in [(collector, bounty), (player, amount - bounty)] {
+        if share > 0 && !token.try_transfer(&env.current_contract_address(), &identity::account(game, to), &share).is_ok_and(|r| r.is_ok()) {
+            return Err(Error::TransferFailed);
+        }
+    }
+    game.remove(SessionKey::Bonded(player.clone()));
+    env.events().publish((symbol_short!("unbonded"), player.clone()), (player.clone(), amount - bounty));
+    Ok(bounty)
+}
+
+fn balance(token: &TokenClient, owner: &Address) -> i128 {
+    match token.try_balance(owner) {
+        Ok(Ok(balance)) => balance,
+        _ => 0,
+    }
+}
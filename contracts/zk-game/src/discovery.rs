@@ -0,0 +1,31 @@
+// ============================================================================
+// Game Discovery
+// ============================================================================
+// Stellar Game Studio launchers query `game_metadata()` on any game contract
+// to list it in generic game browsers. The interface is exported as
+// `GameDiscoveryClient` so launchers and other games can share it.
+
+use soroban_sdk::{contractclient, contracttype, BytesN, Env, String, Symbol, Vec};
+
+/// Self-description of a game contract.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameMetadata {
+    /// Display name
+    pub name: String,
+    /// Contract version (semver)
+    pub version: String,
+    /// Rule variants this build can host
+    pub variants: Vec<Symbol>,
+    /// Location of the rules document
+    pub rules_uri: String,
+    /// SHA-256 of `rules_uri`, for compact comparison
+    pub rules_hash: BytesN<32>,
+    /// Circuit required to produce valid proofs
+    pub circuit_id: Symbol,
+}
+
+#[contractclient(name = "GameDiscoveryClient")]
+pub trait GameDiscovery {
+    fn game_metadata(env: Env) -> GameMetadata;
+}
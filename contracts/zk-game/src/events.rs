@@ -0,0 +1,63 @@
+// ============================================================================
+// Result Events
+// ============================================================================
+// Besides the game-specific `winner` event, the final result is published in
+// the shared Stellar Game Studio result schema so cross-game indexers and the
+// hub explorer can ingest it without battleship-specific decoding.
+//
+// Topics: ("game_result", session_id). Data: `GameResult`.
+
+use soroban_sdk::{contracttype, vec, Address, Symbol, Vec};
+
+use crate::hub::SessionOutcome;
+use crate::{identity, session};
+use crate::storage::{GameStore, SessionKey};
+use crate::Scores;
+
+/// Topic of the shared result event
+pub const RESULT_TOPIC: &str = "game_result";
+
+/// Final result of a session, in the shared cross-game schema.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameResult {
+    /// Game contract that hosted the session
+    pub game_id: Address,
+    pub session_id: u32,
+    /// Participants, in seat order
+    pub players: Vec<Address>,
+    /// Participants' custodial tags, in seat order (0 = untagged)
+    pub tags: Vec<u64>,
+    pub outcome: SessionOutcome,
+    /// Points per participant, in seat order
+    pub points: Vec<i128>,
+    /// Seconds from the start of play to the result (0 if play never started)
+    pub duration: u64,
+    /// Rule variant played
+    pub variant: Symbol,
+}
+
+/// Publish the shared result event for `game`.
+pub fn publish_result(game: &GameStore, outcome: &SessionOutcome, scores: &Scores) {
+    let env = game.env();
+    let (id1, id2) = identity::player_ids(game).unwrap();
+    let duration = match game.get::<u64>(SessionKey::StartedAt) {
+        Some(started) => env.ledger().timestamp().saturating_sub(started),
+        None => 0,
+    };
+
+    let result = GameResult {
+        game_id: env.current_contract_address(),
+        session_id: game.session_id(),
+        players: vec![env, id1.address, id2.address],
+        tags: vec![env, id1.tag, id2.tag],
+        outcome: outcome.clone(),
+        points: vec![env, scores.player1_points, scores.player2_points],
+        duration,
+        variant: session::info(game).variant,
+    };
+    env.events().publish(
+        (Symbol::new(env, RESULT_TOPIC), game.session_id()),
+        result,
+    );
+}
@@ -0,0 +1,91 @@
+// ============================================================================
+// Groth16 Verification (BN254)
+// ============================================================================
+// Soroban adapter over `zk_verifier::groth16`: the key lives in contract
+// storage as a `#[contracttype]`, and is handed to the verifier crate as
+// plain byte arrays. The check itself, and the encoding of keys and 256-byte
+// proofs, are documented there.
+//
+// Keys are held by the verifier contract, see `verifier`.
+
+use soroban_sdk::{contracttype, BytesN, Vec};
+use zk_verifier::groth16;
+
+use crate::bn254::Fr;
+
+/// Groth16 verification key of a circuit.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerificationKey {
+    pub alpha: BytesN<64>,
+    pub beta: BytesN<128>,
+    pub gamma: BytesN<128>,
+    pub delta: BytesN<128>,
+    /// One point per public input, plus the constant term first
+    pub ic: Vec<BytesN<64>>,
+}
+
+impl VerificationKey {
+    fn to_raw(&self) -> groth16::VerificationKey<impl Iterator<Item = [u8; 64]> + '_> {
+        groth16::VerificationKey {
+            alpha: self.alpha.to_array(),
+            beta: self.beta.to_array(),
+            gamma: self.gamma.to_array(),
+            delta: self.delta.to_array(),
+            ic: self.ic.iter().map(|point| point.to_array()),
+        }
+    }
+}
+
+/// Whether every point of `vk` decodes and it has at least the constant term.
+pub fn is_well_formed(vk: &VerificationKey) -> bool {
+    groth16::is_well_formed(vk.to_raw())
+}
+
+/// Whether `proof` is valid for `public_inputs` under `vk`. Malformed
+/// proof points and a wrong input count fail. `vk` must have passed
+/// `is_well_formed`, as every registered key has: its G2 points are not
+/// checked for the subgroup again on each proof.
+pub fn verify(vk: &VerificationKey, proof: &BytesN<256>, public_inputs: &[Fr]) -> bool {
+    // A wrong count fails before any point is decoded
+    vk.ic.len() as usize == public_inputs.len() + 1
+        && groth16::verify_well_formed(vk.to_raw(), &proof.to_array(), public_inputs)
+}
+
+#[cfg(any(test, feature = "testutils"))]
+pub mod testutils {
+    use soroban_sdk::{BytesN, Env, Vec};
+
+    use super::VerificationKey;
+    use crate::bn254::Fr;
+
+    /// `zk_verifier::groth16::testutils::Trapdoor`, producing keys and
+    /// proofs as Soroban values. Tests only: such a key proves nothing.
+    pub struct Trapdoor(zk_verifier::groth16::testutils::Trapdoor);
+
+    impl Trapdoor {
+        pub fn new(inputs: u32) -> Self {
+            Self(zk_verifier::groth16::testutils::Trapdoor::new(inputs))
+        }
+
+        pub fn verification_key(&self, env: &Env) -> VerificationKey {
+            let vk = self.0.verification_key();
+            let mut ic = Vec::new(env);
+            for point in vk.ic {
+                ic.push_back(BytesN::from_array(env, &point));
+            }
+            VerificationKey {
+                alpha: BytesN::from_array(env, &vk.alpha),
+                beta: BytesN::from_array(env, &vk.beta),
+                gamma: BytesN::from_array(env, &vk.gamma),
+                delta: BytesN::from_array(env, &vk.delta),
+                ic,
+            }
+        }
+
+        /// A proof that verifies for `public_inputs` under this key.
+        pub fn prove(&self, env: &Env, public_inputs: &[Fr]) -> BytesN<256> {
+            BytesN::from_array(env, &self.0.prove(public_inputs))
+        }
+    }
+}
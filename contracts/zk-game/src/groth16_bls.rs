@@ -0,0 +1,150 @@
+// ============================================================================
+// Groth16 Verification (BLS12-381)
+// ============================================================================
+// The same check as `groth16`, over the curve Soroban exposes as host
+// functions, so the pairing costs a host call instead of running in the
+// contract. Points use the SDK's uncompressed encoding: G1 96 bytes, G2 192
+// bytes. Proofs are 384 bytes, A (G1) || B (G2) || C (G1).
+//
+// Public inputs stay BN254 field elements, as every game encodes them; each
+// is below the BLS12-381 scalar field order, so it is used unchanged.
+//
+// Points that are not on the curve make the host trap, failing the call;
+// points on the curve but outside the prime-order subgroup fail the check.
+
+use soroban_sdk::crypto::bls12_381::{self, G1Affine, G2Affine};
+use soroban_sdk::{contracttype, BytesN, Env, Vec};
+
+use crate::bn254::Fr;
+
+/// Groth16 verification key of a circuit proven over BLS12-381.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlsVerificationKey {
+    pub alpha: BytesN<96>,
+    pub beta: BytesN<192>,
+    pub gamma: BytesN<192>,
+    pub delta: BytesN<192>,
+    /// One point per public input, plus the constant term first
+    pub ic: Vec<BytesN<96>>,
+}
+
+/// Whether every point of `vk` is in its subgroup and it has at least the
+/// constant term.
+pub fn is_well_formed(env: &Env, vk: &BlsVerificationKey) -> bool {
+    let bls = env.crypto().bls12_381();
+    !vk.ic.is_empty()
+        && bls.g1_is_in_subgroup(&G1Affine::from_bytes(vk.alpha.clone()))
+        && [&vk.beta, &vk.gamma, &vk.delta].iter().all(|point| bls.g2_is_in_subgroup(&G2Affine::from_bytes((*point).clone())))
+        && vk.ic.iter().all(|point| bls.g1_is_in_subgroup(&G1Affine::from_bytes(point)))
+}
+
+/// Whether `proof` is valid for `public_inputs` under `vk`. Proof points
+/// outside the subgroup and a wrong input count fail.
+pub fn verify(env: &Env, vk: &BlsVerificationKey, proof: &BytesN<384>, public_inputs: &[Fr]) -> bool {
+    if vk.ic.len() as usize != public_inputs.len() + 1 {
+        return false;
+    }
+    let bls = env.crypto().bls12_381();
+    let proof = proof.to_array();
+    let a = G1Affine::from_array(env, proof[..96].try_into().unwrap());
+    let b = G2Affine::from_array(env, proof[96..288].try_into().unwrap());
+    let c = G1Affine::from_array(env, proof[288..].try_into().unwrap());
+    if !bls.g1_is_in_subgroup(&a) || !bls.g2_is_in_subgroup(&b) || !bls.g1_is_in_subgroup(&c) {
+        return false;
+    }
+
+    let mut l = G1Affine::from_bytes(vk.ic.get_unchecked(0));
+    for (i, input) in public_inputs.iter().enumerate() {
+        let scalar = bls12_381::Fr::from_bytes(BytesN::from_array(env, &input.to_be_bytes()));
+        let point = G1Affine::from_bytes(vk.ic.get_unchecked(i as u32 + 1));
+        l = bls.g1_add(&l, &bls.g1_mul(&point, &scalar));
+    }
+
+    bls.pairing_check(
+        Vec::from_array(env, [-a, G1Affine::from_bytes(vk.alpha.clone()), l, c]),
+        Vec::from_array(env, [
+            b,
+            G2Affine::from_bytes(vk.beta.clone()),
+            G2Affine::from_bytes(vk.gamma.clone()),
+            G2Affine::from_bytes(vk.delta.clone()),
+        ]),
+    )
+}
+
+#[cfg(any(test, feature = "testutils"))]
+pub mod testutils {
+    use soroban_sdk::crypto::bls12_381::{Fr, G1Affine, G2Affine};
+    use soroban_sdk::{Bytes, BytesN, Env, Vec, U256};
+
+    use super::BlsVerificationKey;
+    use crate::bn254;
+
+    /// `groth16::testutils::Trapdoor` over BLS12-381: keeps its toxic waste,
+    /// so it proves any public inputs without a circuit. Tests only.
+    pub struct BlsTrapdoor {
+        inputs: u32,
+    }
+
+    impl BlsTrapdoor {
+        pub fn new(inputs: u32) -> Self {
+            Self { inputs }
+        }
+
+        pub fn verification_key(&self, env: &Env) -> BlsVerificationKey {
+            let mut ic = Vec::new(env);
+            for i in 0..=self.inputs {
+                ic.push_back(g1(env, &ic_scalar(env, i)).to_bytes());
+            }
+            BlsVerificationKey {
+                alpha: g1(env, &scalar(env, 0x5eed_a1fa)).to_bytes(),
+                beta: g2(env, &scalar(env, 0x5eed_be7a)).to_bytes(),
+                gamma: g2(env, &scalar(env, 0x5eed_9a33)).to_bytes(),
+                delta: g2(env, &scalar(env, 0x5eed_de17)).to_bytes(),
+                ic,
+            }
+        }
+
+        /// A proof that verifies for `public_inputs` under this key.
+        pub fn prove(&self, env: &Env, public_inputs: &[bn254::Fr]) -> BytesN<384> {
+            let (a, b) = (scalar(env, 0xa11ce), scalar(env, 0xb0b));
+            let mut l = ic_scalar(env, 0);
+            for (i, input) in public_inputs.iter().enumerate() {
+                let input = Fr::from_bytes(BytesN::from_array(env, &input.to_be_bytes()));
+                l = l + input * ic_scalar(env, i as u32 + 1);
+            }
+            let alpha_beta = scalar(env, 0x5eed_a1fa) * scalar(env, 0x5eed_be7a);
+            let c = (a.clone() * b.clone() - alpha_beta - l * scalar(env, 0x5eed_9a33))
+                * scalar(env, 0x5eed_de17).inv();
+
+            let mut proof = Bytes::new(env);
+            proof.append(&g1(env, &a).to_bytes().into());
+            proof.append(&g2(env, &b).to_bytes().into());
+            proof.append(&g1(env, &c).to_bytes().into());
+            proof.try_into().unwrap()
+        }
+    }
+
+    fn scalar(env: &Env, value: u32) -> Fr {
+        Fr::from_u256(U256::from_u32(env, value))
+    }
+
+    fn ic_scalar(env: &Env, i: u32) -> Fr {
+        scalar(env, 0x1c00 + i)
+    }
+
+    /// k times a fixed generator of G1; any generator serves a trapdoor.
+    fn g1(env: &Env, k: &Fr) -> G1Affine {
+        let base = env.crypto().bls12_381().hash_to_g1(&Bytes::from_slice(env, b"trapdoor"), &dst(env));
+        env.crypto().bls12_381().g1_mul(&base, k)
+    }
+
+    fn g2(env: &Env, k: &Fr) -> G2Affine {
+        let base = env.crypto().bls12_381().hash_to_g2(&Bytes::from_slice(env, b"trapdoor"), &dst(env));
+        env.crypto().bls12_381().g2_mul(&base, k)
+    }
+
+    fn dst(env: &Env) -> Bytes {
+        Bytes::from_slice(env, b"ZK-GAME-TRAPDOOR-V01-CS01-with-BLS12381G1_XMD:SHA-256_SSWU_RO_")
+    }
+}
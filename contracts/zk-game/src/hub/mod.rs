@@ -0,0 +1,215 @@
+// ============================================================================
+// Game Hub Integration
+// ============================================================================
+// Lifecycle reporting is abstracted behind `HubAdapter` so the contract can
+// report to platforms other than Stellar Game Studio without forking. The
+// interface is chosen per game at initialise; the SGS hub is compiled in by
+// the default `sgs-hub` feature and is the default choice when present.
+//
+// Hubs know players by account, so tagged seats (see `identity`) are
+// reported as the accounts behind them. Hubs other than an SGS v2 hub only
+// register the two seats and their teammates, so a free-for-all won by
+// anyone else is not reported to them.
+//
+// Platforms can halt play during an incident with an emergency-pause flag,
+// read from a deployment-wide pause registry when one is configured and
+// from the game's hub otherwise.
+
+use soroban_sdk::{contractclient, contracttype, log, vec, Address, Env, Vec};
+
+use crate::storage::{GameStore, SessionKey};
+use crate::{identity, team, DataKey, Scores};
+
+#[cfg(feature = "sgs-hub")]
+pub mod sgs;
+
+/// Version recorded for hubs without interface versioning
+pub const HUB_LEGACY: u32 = 1;
+
+/// Hub interface a game reports its lifecycle to.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HubInterface {
+    /// Stellar Game Studio hub (legacy or v2, negotiated)
+    StellarGameStudio,
+    /// Platform-neutral interface, see `GenericHub`
+    Generic,
+}
+
+/// Why a session ended without a result.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AbortReason {
+    /// Cancelled before play started
+    Cancelled,
+    /// A player ran out of time
+    Timeout,
+    /// Nobody touched the game for too long
+    Abandoned,
+}
+
+/// How a session ended.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SessionOutcome {
+    /// The given player won
+    Won(Address),
+    /// Neither player won
+    Draw,
+    /// The session was aborted
+    Aborted(AbortReason),
+}
+
+/// Final result of a session.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionResult {
+    pub outcome: SessionOutcome,
+    /// Points per participant, in seat order
+    pub points: Vec<i128>,
+}
+
+/// Minimal lifecycle interface for non-SGS platforms.
+#[contractclient(name = "GenericHubClient")]
+pub trait GenericHub {
+    fn game_started(env: Env, game_id: Address, session_id: u32, players: Vec<Address>);
+
+    fn game_ended(env: Env, game_id: Address, session_id: u32, result: SessionResult);
+}
+
+/// Emergency-pause flag exposed by a hub or registry.
+#[contractclient(name = "PauseFlagClient")]
+pub trait PauseFlag {
+    fn is_paused(env: Env) -> bool;
+}
+
+/// Reports a game's lifecycle to one kind of hub.
+pub trait HubAdapter {
+    /// Negotiate the interface version spoken by `hub`.
+    fn negotiate_version(&self, env: &Env, hub: &Address) -> u32;
+
+    /// Register the session.
+    fn start(&self, game: &GameStore, hub: &Address, scores: &Scores);
+
+    /// Report how the session ended. Returns whether the hub was notified.
+    fn end(&self, game: &GameStore, hub: &Address, outcome: &SessionOutcome, scores: &Scores) -> bool;
+}
+
+/// Adapter for `GenericHub`.
+pub struct GenericHubAdapter;
+
+impl HubAdapter for GenericHubAdapter {
+    fn negotiate_version(&self, _env: &Env, _hub: &Address) -> u32 {
+        HUB_LEGACY
+    }
+
+    fn start(&self, game: &GameStore, hub: &Address, _scores: &Scores) {
+        let env = game.env();
+        GenericHubClient::new(env, hub).game_started(
+            &env.current_contract_address(),
+            &game.session_id(),
+            &identity::accounts(game, &team::members(game)),
+        );
+    }
+
+    fn end(&self, game: &GameStore, hub: &Address, outcome: &SessionOutcome, scores: &Scores) -> bool {
+        let env = game.env();
+        if !is_seated(game, outcome) {
+            log!(env, "Hub did not register the winner");
+            return false;
+        }
+        let result = SessionResult {
+            outcome: by_account(game, outcome),
+            points: vec![env, scores.player1_points, scores.player2_points],
+        };
+        GenericHubClient::new(env, hub).game_ended(
+            &env.current_contract_address(),
+            &game.session_id(),
+            &result,
+        );
+        true
+    }
+}
+
+/// Interface used by `initialize` when none is given explicitly.
+pub fn default_interface() -> HubInterface {
+    if cfg!(feature = "sgs-hub") {
+        HubInterface::StellarGameStudio
+    } else {
+        HubInterface::Generic
+    }
+}
+
+/// Whether this build can report to `interface`.
+pub fn is_supported(interface: HubInterface) -> bool {
+    match interface {
+        HubInterface::StellarGameStudio => cfg!(feature = "sgs-hub"),
+        HubInterface::Generic => true,
+    }
+}
+
+fn adapter(interface: HubInterface) -> &'static dyn HubAdapter {
+    match interface {
+        #[cfg(feature = "sgs-hub")]
+        HubInterface::StellarGameStudio => &sgs::StellarGameStudioHub,
+        _ => &GenericHubAdapter,
+    }
+}
+
+/// Ask the hub which interface version it speaks.
+pub fn negotiate_version(env: &Env, hub: &Address, interface: HubInterface) -> u32 {
+    adapter(interface).negotiate_version(env, hub)
+}
+
+/// Register the session on the game's hub.
+pub fn report_start(game: &GameStore, scores: &Scores) {
+    let hub: Address = game.get(SessionKey::HubAddress).unwrap();
+    adapter(interface(game)).start(game, &hub, scores);
+}
+
+/// Report how the session ended. Does nothing until the session has been
+/// registered; `register_with_hub()` reports the result itself in that case.
+/// Returns whether the hub was notified.
+pub fn report_end(game: &GameStore, outcome: &SessionOutcome, scores: &Scores) -> bool {
+    let registered: bool = game.get(SessionKey::HubRegistered).unwrap_or(false);
+    if !registered {
+        return false;
+    }
+    let hub: Address = game.get(SessionKey::HubAddress).unwrap();
+    adapter(interface(game)).end(game, &hub, outcome, scores)
+}
+
+/// `outcome` with a winning seat replaced by the account behind it.
+pub(crate) fn by_account(game: &GameStore, outcome: &SessionOutcome) -> SessionOutcome {
+    match outcome {
+        SessionOutcome::Won(winner) => SessionOutcome::Won(identity::account(game, winner)),
+        other => other.clone(),
+    }
+}
+
+/// Whether `outcome` names no winner, or one registered with the hub as a
+/// seat or teammate.
+pub(crate) fn is_seated(game: &GameStore, outcome: &SessionOutcome) -> bool {
+    match outcome {
+        SessionOutcome::Won(winner) => team::members(game).contains(winner),
+        _ => true,
+    }
+}
+
+fn interface(game: &GameStore) -> HubInterface {
+    game.get(SessionKey::HubInterface).unwrap_or(HubInterface::StellarGameStudio)
+}
+
+/// Whether the platform has paused play. A source without a pause flag,
+/// or whose call fails, counts as not paused.
+pub fn is_paused(game: &GameStore) -> bool {
+    let env = game.env();
+    let source = match env.storage().instance().get::<_, Address>(&DataKey::PauseRegistry) {
+        Some(registry) => registry,
+        None => match game.get::<Address>(SessionKey::HubAddress) {
+            Some(hub) => hub,
+            None => return false,
+        },
+    };
+    matches!(PauseFlagClient::new(env, &source).try_is_paused(), Ok(Ok(true)))
+}
@@ -0,0 +1,146 @@
+// ============================================================================
+// Stellar Game Studio Hub
+// ============================================================================
+// The hub contract at CB4VZAT2U3UC6XFK3N23SKRF2NDCMP3QHJYMCHHFMZO7MRQO6DQ2EMYG
+// standardises game lifecycle across all Stellar Game Studio games.
+//
+// Two interface generations exist: the legacy `start_game`/`end_game` pair,
+// and v2, which adds session metadata, draw results and abort reasons. The
+// version is negotiated once at `initialize` and stored with the game, so one
+// contract build works against either hub.
+
+use soroban_sdk::{
+    contractclient, contracttype, log, vec, Address, Env, Symbol, Vec,
+};
+
+use super::{by_account, is_seated, HubAdapter, SessionOutcome, SessionResult, HUB_LEGACY};
+use crate::storage::{GameStore, SessionKey};
+use crate::{identity, session, team};
+use crate::Scores;
+
+/// First hub version exposing the session interface
+pub const HUB_V2: u32 = 2;
+
+#[contractclient(name = "GameHubClient")]
+pub trait GameHub {
+    fn start_game(
+        env: Env,
+        game_id: Address,
+        session_id: u32,
+        player1: Address,
+        player2: Address,
+        player1_points: i128,
+        player2_points: i128,
+    );
+
+    fn end_game(env: Env, session_id: u32, player1_won: bool);
+}
+
+/// Descriptive data attached to a session on a v2 hub.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionMetadata {
+    /// Game name, e.g. `battleship`
+    pub game: Symbol,
+    /// Rule variant being played
+    pub variant: Symbol,
+    /// Participants, in seat order, then any teammates in the order of
+    /// their seats
+    pub players: Vec<Address>,
+    /// Points per participant, in the order of `players`; a teammate plays
+    /// for their seat's points
+    pub points: Vec<i128>,
+}
+
+#[contractclient(name = "GameHubV2Client")]
+pub trait GameHubV2 {
+    fn version(env: Env) -> u32;
+
+    fn start_session(env: Env, game_id: Address, session_id: u32, metadata: SessionMetadata);
+
+    fn end_session(env: Env, session_id: u32, result: SessionResult);
+}
+
+/// Adapter for the Stellar Game Studio hub.
+pub struct StellarGameStudioHub;
+
+impl HubAdapter for StellarGameStudioHub {
+    /// Any failure, including a hub without a `version()` function, falls
+    /// back to the legacy interface.
+    fn negotiate_version(&self, env: &Env, hub: &Address) -> u32 {
+        match GameHubV2Client::new(env, hub).try_version() {
+            Ok(Ok(version)) => version,
+            _ => HUB_LEGACY,
+        }
+    }
+
+    /// The legacy interface takes two players, so teammates are only
+    /// registered with a v2 hub.
+    fn start(&self, game: &GameStore, hub: &Address, scores: &Scores) {
+        let env = game.env();
+        let p1: Address = game.get(SessionKey::Player1).unwrap();
+        let p2: Address = game.get(SessionKey::Player2).unwrap();
+
+        if version(game) >= HUB_V2 {
+            let info = session::info(game);
+            let players = team::members(game);
+            let mut points = vec![env, scores.player1_points, scores.player2_points];
+            for teammate in players.iter().skip(2) {
+                let seat = team::seat(game, &teammate);
+                points.push_back(if seat == p1 { scores.player1_points } else { scores.player2_points });
+            }
+            let metadata = SessionMetadata {
+                game: info.name,
+                variant: info.variant,
+                players: identity::accounts(game, &players),
+                points,
+            };
+            GameHubV2Client::new(env, hub).start_session(
+                &env.current_contract_address(),
+                &game.session_id(),
+                &metadata,
+            );
+        } else {
+            GameHubClient::new(env, hub).start_game(
+                &env.current_contract_address(),
+                &game.session_id(),
+                &identity::account(game, &p1),
+                &identity::account(game, &p2),
+                &scores.player1_points,
+                &scores.player2_points,
+            );
+        }
+    }
+
+    /// The legacy interface can only express a winner among the two seats,
+    /// so draws, aborts and free-for-all wins by other players are not
+    /// reported to it.
+    fn end(&self, game: &GameStore, hub: &Address, outcome: &SessionOutcome, scores: &Scores) -> bool {
+        let env = game.env();
+
+        if version(game) >= HUB_V2 {
+            let result = SessionResult {
+                outcome: by_account(game, outcome),
+                points: vec![env, scores.player1_points, scores.player2_points],
+            };
+            GameHubV2Client::new(env, hub).end_session(&game.session_id(), &result);
+            return true;
+        }
+
+        match outcome {
+            SessionOutcome::Won(winner) if is_seated(game, outcome) => {
+                let p1: Address = game.get(SessionKey::Player1).unwrap();
+                GameHubClient::new(env, hub).end_game(&game.session_id(), &(winner == &p1));
+                true
+            }
+            _ => {
+                log!(env, "Legacy hub cannot record this outcome");
+                false
+            }
+        }
+    }
+}
+
+fn version(game: &GameStore) -> u32 {
+    game.get(SessionKey::HubVersion).unwrap_or(HUB_LEGACY)
+}
@@ -0,0 +1,108 @@
+// ============================================================================
+// Player Identity
+// ============================================================================
+// A seat is normally identified by its address alone. Players behind a
+// custodial or exchange account share that address, so a game can record a
+// memo-style tag per seat; the pair (address, tag) is the player's identity
+// for histories, ratings and indexers. Tag 0 means untagged.
+//
+// Seats are keyed by identity. An untagged player's seat is their account;
+// a tagged player's is an address derived from the account and tag, so two
+// players behind one account hold distinct seats in the same game. Nothing
+// is deployed at a derived seat: the account authorises the seat's moves,
+// posts its stake and bond and takes its payouts.
+
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Vec};
+
+use crate::session;
+use crate::storage::{GameStore, SessionKey};
+use crate::Error;
+
+/// Composite identity of a player.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlayerId {
+    /// Account that authorises the player's moves
+    pub address: Address,
+    /// Sub-identifier within the account (0 = none)
+    pub tag: u64,
+}
+
+impl PlayerId {
+    /// Identity of an untagged player.
+    pub fn untagged(address: Address) -> Self {
+        Self { address, tag: 0 }
+    }
+}
+
+/// Address `id`'s seat is keyed by.
+pub fn seat(env: &Env, id: &PlayerId) -> Address {
+    if id.tag == 0 {
+        return id.address.clone();
+    }
+    let mut salt = [0u8; 32];
+    salt[24..].copy_from_slice(&id.tag.to_be_bytes());
+    env.deployer()
+        .with_address(id.address.clone(), BytesN::from_array(env, &salt))
+        .deployed_address()
+}
+
+/// Record the seats' identities, once `session::init` has seated them, and
+/// announce them if either is tagged.
+pub fn register(game: &GameStore, player1: &PlayerId, player2: &PlayerId) -> Result<(), Error> {
+    let (p1, p2) = session::players(game)?;
+    game.remove(SessionKey::Tags);
+    game.remove(SessionKey::Account(p1.clone()));
+    game.remove(SessionKey::Account(p2.clone()));
+    if player1.tag == 0 && player2.tag == 0 {
+        return Ok(());
+    }
+    game.set(SessionKey::Tags, &(player1.tag, player2.tag));
+    for (seat, id) in [(p1, player1), (p2, player2)] {
+        if id.tag != 0 {
+            game.set(SessionKey::Account(seat), &id.address);
+        }
+    }
+    game.env().events().publish(
+        (symbol_short!("ids"), game.session_id()),
+        (player1.clone(), player2.clone()),
+    );
+    Ok(())
+}
+
+/// Account behind `seat`; any other address stands for itself.
+pub fn account(game: &GameStore, seat: &Address) -> Address {
+    game.get(SessionKey::Account(seat.clone())).unwrap_or_else(|| seat.clone())
+}
+
+/// Accounts behind `members`, in order.
+pub fn accounts(game: &GameStore, members: &Vec<Address>) -> Vec<Address> {
+    let mut accounts = Vec::new(game.env());
+    for member in members.iter() {
+        accounts.push_back(account(game, &member));
+    }
+    accounts
+}
+
+/// Require the authorisation of the account behind `seat`.
+pub fn require_auth(game: &GameStore, seat: &Address) {
+    account(game, seat).require_auth();
+}
+
+/// Both seats' identities, in seat order.
+pub fn player_ids(game: &GameStore) -> Result<(PlayerId, PlayerId), Error> {
+    let (p1, p2) = session::players(game)?;
+    let (tag1, tag2): (u64, u64) = game.get(SessionKey::Tags).unwrap_or((0, 0));
+    Ok((
+        PlayerId { address: account(game, &p1), tag: tag1 },
+        PlayerId { address: account(game, &p2), tag: tag2 },
+    ))
+}
+
+/// Identity of the seat held by `player`.
+pub fn player_id(game: &GameStore, player: &Address) -> Result<PlayerId, Error> {
+    let (p1, _) = session::players(game)?;
+    session::require_player(game, player)?;
+    let (id1, id2) = player_ids(game)?;
+    Ok(if player == &p1 { id1 } else { id2 })
+}
@@ -0,0 +1,172 @@
+#![no_std]
+
+// ============================================================================
+// ZK Game Framework
+// ============================================================================
+// The machinery shared by two-player, turn-based Stellar Game Studio games
+// with hidden state proven in zero knowledge: the session lifecycle and
+// commit/turn/proof phase machine, per-session storage, spent proof
+// nullifiers, hub reporting, wager escrow and cheating bonds, the
+// deployment services games rely on (randomness, price oracle, pause flag,
+// player profiles, proof verifier), and the Groth16 verification over BN254
+// and BLS12-381 that the verifier contract runs. The BN254 math itself
+// lives in the soroban-free `zk-verifier` crate; `groth16` adapts it to contract types.
+//
+// A game contract owns its rules and its own error type; framework errors
+// convert into it with `From<Error>`. See the battleship contract for the
+// reference integration.
+
+use soroban_sdk::{contracttype, Address, BytesN, Symbol};
+
+pub mod admin;
+pub mod attestation;
+pub mod bond;
+pub mod discovery;
+pub mod events;
+pub mod groth16;
+pub mod groth16_bls;
+pub mod hub;
+pub mod identity;
+pub mod nullifier;
+pub mod oracle;
+pub mod poseidon2;
+pub mod profiles;
+pub mod proof;
+pub mod randomness;
+pub mod session;
+pub mod snapshot;
+pub mod storage;
+pub mod team;
+pub mod verifier;
+pub mod wager;
+
+pub use session::GamePhase;
+/// BN254 arithmetic, from the standalone `zk-verifier` crate.
+pub use zk_verifier::bn254;
+
+/// Errors raised by the framework. Game contracts map these onto their own
+/// `#[contracterror]` enum.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// Game not found or not initialised
+    NotInitialized,
+    /// Action not allowed in current game phase
+    InvalidPhase,
+    /// Caller is not a participant in this game
+    NotAPlayer,
+    /// Session already registered with the hub
+    AlreadyRegistered,
+    /// Hub interface not compiled into this build
+    UnsupportedHub,
+    /// Wager missing, non-positive, or not a SEP-41 token
+    InvalidWager,
+    /// Stake already deposited by this player
+    AlreadyDeposited,
+    /// Token transfer was refused
+    TransferFailed,
+    /// Escrow received less than the stake
+    DepositShortfall,
+    /// No held payout for this player
+    NothingToWithdraw,
+    /// Allowance granted to this contract is below the stake
+    InsufficientAllowance,
+    /// No deployment admin has been set
+    AdminNotSet,
+    /// No fresh oracle price for the wager token
+    PriceUnavailable,
+    /// Wager's USD value is outside the deployment's limits
+    WagerOutOfRange,
+    /// Play is halted by the platform's emergency pause
+    Paused,
+    /// Both seats were given the same account
+    DuplicatePlayer,
+    /// Verification key has a malformed point or no constant term
+    InvalidVerificationKey,
+    /// Proof exceeds `proof::MAX_PROOF_SIZE`
+    ProofTooLarge,
+    /// No verifier for the proof's system in this build
+    UnsupportedProofSystem,
+    /// No proof verifier contract has been configured
+    VerifierNotSet,
+    /// Proof verifier contract could not be called
+    VerifierUnavailable,
+    /// Circuit id already registered
+    CircuitExists,
+    /// No circuit registered under this id
+    UnknownCircuit,
+    /// Circuit version has too many or unknown public inputs
+    InvalidCircuit,
+    /// Circuit or service already configured; replace it with a timelocked upgrade
+    KeyAlreadySet,
+    /// No key upgrade or service change is ready: none was proposed, or its timelock has not passed
+    KeyUpgradeNotReady,
+    /// Snapshot is not the last one exported for its session
+    SnapshotMismatch,
+    /// Session is still being played; it must finish or be reset first
+    AlreadyInitialized,
+}
+
+/// Hub points earned by each player so far.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Scores {
+    pub player1_points: i128,
+    pub player2_points: i128,
+}
+
+/// Contract-level storage keys. Per-game entries are keyed separately,
+/// see `storage::GameStore`.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// Deployment administrator (instance storage)
+    Admin,
+    /// Randomness beacon contract for this deployment (instance storage)
+    RandomnessBeacon,
+    /// Price oracle contract for this deployment (instance storage)
+    PriceOracle,
+    /// USD bounds on wagers for this deployment (instance storage)
+    WagerLimits,
+    /// Contract publishing the emergency-pause flag, overriding the hub (instance storage)
+    PauseRegistry,
+    /// Profile registry used to resolve player names (instance storage)
+    ProfileRegistry,
+    /// Proof verifier contract for this deployment (instance storage)
+    Verifier,
+    /// Verifier key id of one of the game's circuits (instance storage)
+    VerificationKey(Symbol),
+    /// Key proposed to replace a circuit's, awaiting its timelock (instance storage)
+    KeyUpgrade(Symbol),
+    /// Change proposed to a deployment service, awaiting its timelock (instance storage)
+    ServiceChange(admin::Service),
+    /// Registered circuit version, by circuit id (instance storage)
+    Circuit(Symbol),
+    /// Invalid proofs a player may submit in one game before forfeiting it (instance storage)
+    StrikeLimit,
+    /// Sessions created and not yet finished (instance storage)
+    OpenSessions,
+    /// Sessions started and not yet finished (instance storage)
+    SessionsInPlay,
+    /// Sessions ever created, the length of the session index (instance storage)
+    SessionCount,
+    /// Session id of the nth session created (persistent storage)
+    SessionAt(u32),
+    /// Sessions a player was seated in, the length of their index (persistent storage)
+    PlayerSessionCount(Address),
+    /// Session id of the nth session a player was seated in (persistent storage)
+    PlayerSessionAt(Address, u32),
+    /// Marks a session whose entries live in persistent storage, see
+    /// `storage::StorageTier` (persistent storage)
+    PersistentSession(u32),
+    /// Digest of the last snapshot exported of a session, see `snapshot`
+    /// (persistent storage)
+    Snapshot(u32),
+    /// Bond players post when a game is created, see `bond` (instance storage)
+    Bond,
+    /// Spent nullifier, see `nullifier` (persistent storage)
+    Nullifier(BytesN<32>),
+    /// Puzzle board published by an operator, by puzzle id (persistent storage)
+    Puzzle(u32),
+    /// Best shot counts solving a puzzle, by puzzle id (persistent storage)
+    PuzzleLeaderboard(u32),
+}
@@ -0,0 +1,35 @@
+// ============================================================================
+// Nullifiers
+// ============================================================================
+// A nullifier names an action that may happen once, such as answering one
+// cell of one committed board, and is a public input of the proof that
+// performs it. The contract spends each nullifier it accepts; a second proof
+// carrying it is refused.
+//
+// Spent nullifiers live in persistent storage at contract level, outside any
+// session's namespace, so they hold even if a game's own bookkeeping is
+// lost, cleared or migrated.
+
+use soroban_sdk::{BytesN, Env};
+
+use crate::DataKey;
+
+/// Ledgers a spent nullifier is kept for (about 30 days of 5-second
+/// ledgers, a session's lifetime)
+pub const NULLIFIER_TTL: u32 = 30 * 24 * 60 * 60 / 5;
+
+/// Whether `nullifier` has been spent.
+pub fn is_spent(env: &Env, nullifier: &BytesN<32>) -> bool {
+    env.storage().persistent().has(&DataKey::Nullifier(nullifier.clone()))
+}
+
+/// Spend `nullifier`; `false`, changing nothing, if it was spent already.
+pub fn spend(env: &Env, nullifier: &BytesN<32>) -> bool {
+    if is_spent(env, nullifier) {
+        return false;
+    }
+    let key = DataKey::Nullifier(nullifier.clone());
+    env.storage().persistent().set(&key, &true);
+    env.storage().persistent().extend_ttl(&key, NULLIFIER_TTL, NULLIFIER_TTL);
+    true
+}
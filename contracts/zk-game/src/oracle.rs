@@ -0,0 +1,100 @@
+// ============================================================================
+// Price Oracle
+// ============================================================================
+// Wager ladders and tier limits are expressed in USD while stakes are made in
+// arbitrary SEP-41 assets. A Reflector-style oracle converts between the two
+// at lock-in time (when the wager terms are set) and the rate used is kept
+// with the wager for the payout record.
+//
+// USD values use the oracle's own fixed-point precision (`decimals()`).
+
+use soroban_sdk::{contractclient, contracttype, Address, Env, Symbol};
+
+use crate::{DataKey, Error};
+
+/// Oldest price accepted at lock-in, in seconds
+pub const MAX_PRICE_AGE: u64 = 15 * 60;
+
+/// Asset identifier in the Reflector interface.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    /// A Stellar asset, by its token contract
+    Stellar(Address),
+    /// An off-chain asset, by ticker
+    Other(Symbol),
+}
+
+/// Price quote in the Reflector interface.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    /// USD price in oracle decimals
+    pub price: i128,
+    /// Quote time, unix seconds
+    pub timestamp: u64,
+}
+
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    fn decimals(env: Env) -> u32;
+
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+}
+
+/// Deployment-wide bounds on a wager's USD value. Zero means unbounded.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UsdLimits {
+    pub min_usd: i128,
+    pub max_usd: i128,
+}
+
+/// Oracle configured for this deployment, if any.
+pub fn oracle(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::PriceOracle)
+}
+
+/// Wager limits configured for this deployment, if any.
+pub fn limits(env: &Env) -> Option<UsdLimits> {
+    env.storage().instance().get(&DataKey::WagerLimits)
+}
+
+/// Fresh USD price of `token`, in oracle decimals.
+pub fn price(env: &Env, token: &Address) -> Result<PriceData, Error> {
+    let oracle = oracle(env).ok_or(Error::PriceUnavailable)?;
+    let quote = match PriceOracleClient::new(env, &oracle).try_lastprice(&Asset::Stellar(token.clone())) {
+        Ok(Ok(Some(quote))) => quote,
+        _ => return Err(Error::PriceUnavailable),
+    };
+    let age = env.ledger().timestamp().saturating_sub(quote.timestamp);
+    if quote.price <= 0 || age > MAX_PRICE_AGE {
+        return Err(Error::PriceUnavailable);
+    }
+    Ok(quote)
+}
+
+/// USD value of `amount` base units of a token with `decimals`, or
+/// `WagerOutOfRange` if it does not fit in an `i128`.
+pub fn to_usd(amount: i128, decimals: u32, price: i128) -> Result<i128, Error> {
+    let scale = 10_i128.checked_pow(decimals).ok_or(Error::WagerOutOfRange)?;
+    Ok(amount.checked_mul(price).ok_or(Error::WagerOutOfRange)? / scale)
+}
+
+/// Base units of a token with `decimals` worth `usd`, or `WagerOutOfRange`
+/// if they do not fit in an `i128`.
+pub fn from_usd(usd: i128, decimals: u32, price: i128) -> Result<i128, Error> {
+    let scale = 10_i128.checked_pow(decimals).ok_or(Error::WagerOutOfRange)?;
+    Ok(usd.checked_mul(scale).ok_or(Error::WagerOutOfRange)? / price)
+}
+
+/// Check a USD value against the deployment limits.
+pub fn check_limits(env: &Env, usd: i128) -> Result<(), Error> {
+    let Some(limits) = limits(env) else {
+        return Ok(());
+    };
+    if (limits.min_usd > 0 && usd < limits.min_usd) || (limits.max_usd > 0 && usd > limits.max_usd) {
+        return Err(Error::WagerOutOfRange);
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+// ============================================================================
+// Player Profiles
+// ============================================================================
+// Display names and avatars come from a profile registry contract when the
+// deployment configures one, so front-ends don't each resolve them. Players
+// without a profile (or with no registry, or a failing one) fall back to
+// their address.
+
+use soroban_sdk::{contractclient, contracttype, Address, Env, String};
+
+use crate::DataKey;
+
+/// Profile as published by the registry.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    /// Avatar image URI
+    pub avatar: String,
+}
+
+#[contractclient(name = "ProfileRegistryClient")]
+pub trait ProfileRegistry {
+    fn profile(env: Env, owner: Address) -> Option<Profile>;
+}
+
+/// A player with their resolved profile.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlayerProfile {
+    pub address: Address,
+    /// Display name, or the address (strkey) when unresolved
+    pub name: String,
+    /// Avatar URI, empty when unresolved
+    pub avatar: String,
+    /// Whether the registry supplied the profile
+    pub resolved: bool,
+}
+
+/// Registry configured for this deployment, if any.
+pub fn registry(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::ProfileRegistry)
+}
+
+/// Resolve `player`'s profile, falling back to their address.
+pub fn resolve(env: &Env, player: &Address) -> PlayerProfile {
+    let profile = registry(env).and_then(|registry| {
+        match ProfileRegistryClient::new(env, &registry).try_profile(player) {
+            Ok(Ok(profile)) => profile,
+            _ => None,
+        }
+    });
+
+    match profile {
+        Some(profile) => PlayerProfile {
+            address: player.clone(),
+            name: profile.name,
+            avatar: profile.avatar,
+            resolved: true,
+        },
+        None => PlayerProfile {
+            address: player.clone(),
+            name: player.to_string(),
+            avatar: String::from_str(env, ""),
+            resolved: false,
+        },
+    }
+}
@@ -0,0 +1,98 @@
+// ============================================================================
+// Proof Dispatch
+// ============================================================================
+// Proofs travel as variable-length bytes tagged with the system that made
+// them, so a game's interface does not change when its circuits move to
+// another prover (e.g. from Groth16 to the UltraHonk proofs of newer Noir
+// toolchains). Each system decodes and checks its own encoding; a system
+// with no in-contract verifier yet is refused rather than accepted.
+//
+// Groth16 over BN254 is checked only with the `bn254-verifier` feature;
+// over BLS12-381 it always is, with the host's pairing. A build with
+// `mock-verifier` alone accepts every proof, so a local sandbox can play
+// with placeholder proofs; the verifier contract will not build for release
+// that way.
+
+use soroban_sdk::{contracttype, Bytes, BytesN, Env};
+
+use crate::bn254::Fr;
+use crate::groth16::{self, VerificationKey};
+use crate::groth16_bls::{self, BlsVerificationKey};
+use crate::Error;
+
+/// Largest proof accepted, in bytes: room for an UltraHonk proof over
+/// BN254 (about 14 KB) while bounding what a caller can make us read.
+pub const MAX_PROOF_SIZE: u32 = 16 * 1024;
+
+/// Proving system a proof was generated with.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ProofSystem {
+    /// Groth16 over BN254, 256 bytes in the `bn254` encoding
+    Groth16,
+    /// Barretenberg UltraHonk over BN254
+    UltraHonk,
+    /// Groth16 over BLS12-381, 384 bytes in the `groth16_bls` encoding
+    Groth16Bls12381,
+}
+
+/// A proof and the system that produced it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proof {
+    pub system: ProofSystem,
+    pub bytes: Bytes,
+}
+
+impl Proof {
+    pub fn groth16(proof: &BytesN<256>) -> Self {
+        Self { system: ProofSystem::Groth16, bytes: proof.into() }
+    }
+
+    pub fn groth16_bls12_381(proof: &BytesN<384>) -> Self {
+        Self { system: ProofSystem::Groth16Bls12381, bytes: proof.into() }
+    }
+}
+
+/// Whether the proof system works over BLS12-381, and so is checked
+/// against a `BlsVerificationKey`.
+pub fn is_bls12_381(system: ProofSystem) -> bool {
+    system == ProofSystem::Groth16Bls12381
+}
+
+/// Whether `proof` is valid for `public_inputs` under `vk`. Oversized proofs
+/// and proofs from a system this build cannot verify are errors; a proof
+/// that is merely wrong, including one of the wrong length, is `Ok(false)`.
+pub fn verify(vk: &VerificationKey, proof: &Proof, public_inputs: &[Fr]) -> Result<bool, Error> {
+    if proof.bytes.len() > MAX_PROOF_SIZE {
+        return Err(Error::ProofTooLarge);
+    }
+    if cfg!(all(feature = "mock-verifier", not(feature = "bn254-verifier"))) {
+        return Ok(true);
+    }
+    match proof.system {
+        ProofSystem::Groth16 if cfg!(feature = "bn254-verifier") => Ok(BytesN::<256>::try_from(&proof.bytes)
+            .is_ok_and(|bytes| groth16::verify(vk, &bytes, public_inputs))),
+        _ => Err(Error::UnsupportedProofSystem),
+    }
+}
+
+/// As `verify`, for a proof over BLS12-381 under `vk`.
+pub fn verify_bls12_381(
+    env: &Env,
+    vk: &BlsVerificationKey,
+    proof: &Proof,
+    public_inputs: &[Fr],
+) -> Result<bool, Error> {
+    if proof.bytes.len() > MAX_PROOF_SIZE {
+        return Err(Error::ProofTooLarge);
+    }
+    if cfg!(all(feature = "mock-verifier", not(feature = "bn254-verifier"))) {
+        return Ok(true);
+    }
+    match proof.system {
+        ProofSystem::Groth16Bls12381 => Ok(BytesN::<384>::try_from(&proof.bytes)
+            .is_ok_and(|bytes| groth16_bls::verify(env, vk, &bytes, public_inputs))),
+        _ => Err(Error::UnsupportedProofSystem),
+    }
+}
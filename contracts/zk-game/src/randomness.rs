@@ -0,0 +1,132 @@
+// ============================================================================
+// Randomness
+// ============================================================================
+// Random choices (first mover, daily challenge seeds, storm cells) come from
+// an external beacon contract when the deployment configures one, and from
+// the ledger PRNG otherwise. The ledger PRNG can be predicted and steered by
+// whoever submits the transaction, so variants where that matters should
+// configure a beacon.
+//
+// The first mover can instead be settled between the players by a coin
+// flip: each commits to a nonce, both reveal once both have committed, and
+// the XOR of the nonces decides. Neither can steer it without the other.
+// Whoever reveals second has seen the other's nonce, so must reveal within
+// `COIN_REVEAL_WINDOW` ledgers or forfeit the flip to them.
+
+use soroban_sdk::{contractclient, xdr::ToXdr, Address, Bytes, BytesN, Env};
+
+use crate::storage::{GameStore, SessionKey};
+use crate::{session, DataKey};
+
+/// Seed used to pick which player moves first
+pub const PURPOSE_FIRST_MOVER: u32 = 1;
+/// Seed for a daily challenge board
+pub const PURPOSE_DAILY_CHALLENGE: u32 = 2;
+/// Seed for storm cells in weather variants
+pub const PURPOSE_STORM: u32 = 3;
+
+/// Ledgers the second coin-flip nonce may be revealed in after the first
+pub const COIN_REVEAL_WINDOW: u32 = 720;
+
+/// Verifiable randomness source, e.g. a drand relay.
+#[contractclient(name = "RandomnessBeaconClient")]
+pub trait RandomnessBeacon {
+    /// Most recent round with published randomness
+    fn latest_round(env: Env) -> u64;
+
+    /// Verified randomness of a round
+    fn randomness(env: Env, round: u64) -> BytesN<32>;
+}
+
+/// Beacon configured for this deployment, if any.
+pub fn beacon(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::RandomnessBeacon)
+}
+
+/// Beacon randomness for `purpose` in this game, with the round it came
+/// from. `None` if no beacon is configured or the beacon call fails.
+pub fn beacon_seed(game: &GameStore, purpose: u32) -> Option<(BytesN<32>, u64)> {
+    let env = game.env();
+    let client = RandomnessBeaconClient::new(env, &beacon(env)?);
+    let round = client.try_latest_round().ok()?.ok()?;
+    let raw = client.try_randomness(&round).ok()?.ok()?;
+    Some((derive(game, &raw, purpose), round))
+}
+
+/// Randomness for `purpose` in this game: the beacon when available,
+/// otherwise the ledger PRNG.
+pub fn seed(game: &GameStore, purpose: u32) -> BytesN<32> {
+    match beacon_seed(game, purpose) {
+        Some((seed, _)) => seed,
+        None => {
+            let raw: BytesN<32> = game.env().prng().gen();
+            derive(game, &raw, purpose)
+        }
+    }
+}
+
+/// Record `player`'s coin-flip commitment: sha256 of their 32-byte nonce.
+pub fn commit_coin(game: &GameStore, player: &Address, commitment: &BytesN<32>) {
+    game.set(SessionKey::CoinCommit(player.clone()), commitment);
+}
+
+/// Whether `player` has committed to a coin-flip nonce.
+pub fn has_coin(game: &GameStore, player: &Address) -> bool {
+    game.has(SessionKey::CoinCommit(player.clone()))
+}
+
+/// Record `player`'s revealed nonce if it opens their commitment, starting
+/// the other player's reveal window if it is the first. Returns whether it
+/// did.
+pub fn reveal_coin(game: &GameStore, player: &Address, nonce: &BytesN<32>) -> bool {
+    let env = game.env();
+    let commitment: Option<BytesN<32>> = game.get(SessionKey::CoinCommit(player.clone()));
+    let hash = env.crypto().sha256(&Bytes::from_array(env, &nonce.to_array())).to_bytes();
+    if commitment != Some(hash) {
+        return false;
+    }
+    game.set(SessionKey::CoinNonce(player.clone()), nonce);
+    if !game.has(SessionKey::CoinDeadline) {
+        game.set(SessionKey::CoinDeadline, &(env.ledger().sequence() + COIN_REVEAL_WINDOW));
+    }
+    true
+}
+
+/// Last ledger the second nonce may be revealed in, once the first is.
+pub fn coin_deadline(game: &GameStore) -> Option<u32> {
+    game.get(SessionKey::CoinDeadline)
+}
+
+/// Settle the coin flip against `player`, who did not reveal their nonce
+/// by the deadline: the other player moves first.
+pub fn forfeit_coin(game: &GameStore, player: &Address) {
+    game.set(SessionKey::CoinForfeit, player);
+}
+
+/// Whether `player` has revealed their coin-flip nonce.
+pub fn coin_revealed(game: &GameStore, player: &Address) -> bool {
+    game.has(SessionKey::CoinNonce(player.clone()))
+}
+
+/// Outcome of the coin flip once both nonces are revealed, or one player
+/// forfeited it: whether player 2 moves first, the low bit of the nonces'
+/// XOR.
+pub fn coin_flip(game: &GameStore) -> Option<bool> {
+    let (p1, p2) = session::players(game).ok()?;
+    if let Some(forfeited) = game.get::<Address>(SessionKey::CoinForfeit) {
+        return Some(forfeited == p1);
+    }
+    let nonce1: BytesN<32> = game.get(SessionKey::CoinNonce(p1))?;
+    let nonce2: BytesN<32> = game.get(SessionKey::CoinNonce(p2))?;
+    Some((nonce1.to_array()[31] ^ nonce2.to_array()[31]) & 1 == 1)
+}
+
+/// Domain-separate raw randomness by session and purpose, so one beacon
+/// round never yields the same value for two games or two uses.
+fn derive(game: &GameStore, raw: &BytesN<32>, purpose: u32) -> BytesN<32> {
+    let env = game.env();
+    let mut input = Bytes::from_array(env, &raw.to_array());
+    input.append(&game.session_id().to_xdr(env));
+    input.append(&purpose.to_xdr(env));
+    env.crypto().sha256(&input).to_bytes()
+}
@@ -0,0 +1,613 @@
+// ============================================================================
+// Session Lifecycle
+// ============================================================================
+// Two seats, a commit phase in which both players lock their hidden state,
+// then alternating turns where each move waits for the opponent's proof:
+//
+//   WaitingForCommits -> PlayerNTurn -> WaitingForProof -> PlayerMTurn ... -> Finished
+//
+// A game may instead have both players move every round, never alternating:
+//
+//   WaitingForCommits -> RoundCommit -> RoundReveal -> RoundProve -> RoundCommit ... -> Finished
+//
+// Play starts once the game reports both commitments made and any wager is
+// funded. The game decides when a proof ends the session; `finish` then
+// settles the wager, reports to the hub, and publishes the result. A game
+// that audits its players after the fact can hold that settlement, and
+// `settle` it later with the result the audit leaves.
+//
+// A session may have a turn timeout: every phase change then sets a
+// deadline ledger, after which the game can forfeit the session to the
+// player who was kept waiting. It may also give each player a chess clock,
+// a budget of ledgers for the whole game that runs while play waits on them.
+
+use soroban_sdk::{contracttype, log, symbol_short, Address, Env, Symbol, TryFromVal, Val, Vec};
+
+use crate::hub::{self, AbortReason, HubInterface, SessionOutcome};
+use crate::storage::{GameStore, SessionKey};
+use crate::{events, randomness, snapshot, wager, DataKey, Error, Scores};
+
+/// Ledgers a session's entries, and its place in the contract's index, are
+/// kept alive for past their last extension (about 30 days of 5-second
+/// ledgers)
+pub const SESSION_TTL: u32 = 30 * 24 * 60 * 60 / 5;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GamePhase {
+    /// Waiting for both players to commit their hidden state
+    WaitingForCommits,
+    /// Player 1's turn to move
+    Player1Turn,
+    /// Player 2's turn to move
+    Player2Turn,
+    /// Waiting for the opponent to submit a ZK proof response
+    WaitingForProof,
+    /// Simultaneous fire: waiting for both players to commit their shot
+    RoundCommit,
+    /// Simultaneous fire: waiting for both players to reveal their shot
+    RoundReveal,
+    /// Simultaneous fire: waiting for both players' proven responses
+    RoundProve,
+    /// Game is over, winner determined
+    Finished,
+}
+
+/// A player's chess clock.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Clock {
+    /// Ledgers the clock holds for the whole game (0 = untimed)
+    pub budget: u32,
+    /// Ledgers used so far, the running move included
+    pub used: u32,
+    /// Whether it is running, i.e. play waits on the player
+    pub running: bool,
+}
+
+/// Where a session stands, for lobby listings.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SessionStatus {
+    /// Waiting for commitments
+    Open,
+    /// Being played
+    InProgress,
+    /// Over
+    Finished,
+}
+
+/// One session in a lobby listing.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionSummary {
+    pub session_id: u32,
+    pub player1: Address,
+    pub player2: Address,
+    pub status: SessionStatus,
+}
+
+/// What is being played in a session.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameInfo {
+    /// Game name, e.g. `battleship`
+    pub name: Symbol,
+    /// Rule variant being played
+    pub variant: Symbol,
+}
+
+/// Create a session between two players, alongside any other sessions this
+/// contract hosts. The hub is only asked for its interface version here.
+pub fn init(
+    game: &GameStore,
+    hub_address: &Address,
+    hub_interface: HubInterface,
+    player1: &Address,
+    player2: &Address,
+    info: &GameInfo,
+) -> Result<(), Error> {
+    let env = game.env();
+    if !hub::is_supported(hub_interface) {
+        return Err(Error::UnsupportedHub);
+    }
+    // Seats are told apart by address; custodial players sharing an account
+    // hold distinct seats by tag (see `identity`)
+    if player1 == player2 {
+        return Err(Error::DuplicatePlayer);
+    }
+
+    // A finished session can be created again, without the outcome of its
+    // last game; one still open cannot
+    match phase(game) {
+        Ok(phase) if phase != GamePhase::Finished => return Err(Error::AlreadyInitialized),
+        // Nor can one whose result is still held
+        Ok(_) if settle_deadline(game).is_some() => return Err(Error::AlreadyInitialized),
+        Ok(_) => {
+            // A payout the token refused stays withdrawable under the old
+            // wager's terms until it is claimed
+            let (p1, p2) = players(game)?;
+            if wager::held_payout(game, &p1) > 0 || wager::held_payout(game, &p2) > 0 {
+                return Err(Error::AlreadyInitialized);
+            }
+            // Nothing of the last game's terms, stakes, clocks or seats
+            // carries over
+            for key in game.keys().iter() {
+                game.remove(key);
+            }
+            count(env, DataKey::OpenSessions, 1);
+            index_player(game, player1);
+            index_player(game, player2);
+        }
+        Err(_) => {
+            count(env, DataKey::OpenSessions, 1);
+            index(game);
+            index_player(game, player1);
+            index_player(game, player2);
+        }
+    }
+    // A snapshot of an earlier game under this id, lapsed before it
+    // finished, must not restore over this one
+    snapshot::forget(game);
+
+    game.set(SessionKey::HubAddress, hub_address);
+    game.set(SessionKey::Player1, player1);
+    game.set(SessionKey::Player2, player2);
+    game.set(SessionKey::Phase, &GamePhase::WaitingForCommits);
+    game.set(SessionKey::HubRegistered, &false);
+    game.set(SessionKey::HubInterface, &hub_interface);
+    game.set(SessionKey::Info, info);
+    let version = hub::negotiate_version(env, hub_address, hub_interface);
+    game.set(SessionKey::HubVersion, &version);
+    Ok(())
+}
+
+/// Both seats, in order.
+pub fn players(game: &GameStore) -> Result<(Address, Address), Error> {
+    let p1: Address = game.get(SessionKey::Player1).ok_or(Error::NotInitialized)?;
+    let p2: Address = game.get(SessionKey::Player2).ok_or(Error::NotInitialized)?;
+    Ok((p1, p2))
+}
+
+pub fn phase(game: &GameStore) -> Result<GamePhase, Error> {
+    game.get(SessionKey::Phase).ok_or(Error::NotInitialized)
+}
+
+/// Verify `player` holds one of the seats.
+pub fn require_player(game: &GameStore, player: &Address) -> Result<(), Error> {
+    let (p1, p2) = players(game)?;
+    if player != &p1 && player != &p2 {
+        return Err(Error::NotAPlayer);
+    }
+    Ok(())
+}
+
+/// The other seat.
+pub fn opponent(game: &GameStore, player: &Address) -> Result<Address, Error> {
+    let (p1, p2) = players(game)?;
+    Ok(if player == &p1 { p2 } else { p1 })
+}
+
+/// Reject moves and settlements while the platform is paused.
+pub fn require_not_paused(game: &GameStore) -> Result<(), Error> {
+    if hub::is_paused(game) {
+        return Err(Error::Paused);
+    }
+    Ok(())
+}
+
+/// Start play once both players have committed and any wager is funded.
+/// Player 1 moves first unless the players flipped a coin for it, or the
+/// deployment has a randomness beacon, in which case the coin or the beacon
+/// picks the first mover. Returns whether play started.
+pub fn start_if_ready(game: &GameStore, both_committed: bool) -> bool {
+    let env = game.env();
+    let (p1, p2) = players(game).unwrap();
+    if !both_committed || !wager::is_funded(game, &p1, &p2) {
+        return false;
+    }
+
+    let coin = randomness::coin_flip(game);
+    let beacon = coin.is_none().then(|| randomness::beacon_seed(game, randomness::PURPOSE_FIRST_MOVER)).flatten();
+    let p2_first = match (coin, beacon) {
+        (Some(p2_first), _) => {
+            let first = if p2_first { &p2 } else { &p1 };
+            env.events().publish(
+                (symbol_short!("coin"),),
+                first.clone(),
+            );
+            p2_first
+        }
+        (None, Some((seed, round))) => {
+            let p2_first = seed.get(0).unwrap_or(0) & 1 == 1;
+            let first = if p2_first { &p2 } else { &p1 };
+            env.events().publish(
+                (symbol_short!("first"),),
+                (first.clone(), round),
+            );
+            p2_first
+        }
+        (None, None) => false,
+    };
+    set_phase(game, if p2_first { GamePhase::Player2Turn } else { GamePhase::Player1Turn });
+    switch_clock(game, Some(if p2_first { &p2 } else { &p1 }));
+    game.set(SessionKey::StartedAt, &env.ledger().timestamp());
+    count(env, DataKey::SessionsInPlay, 1);
+    env.events().publish(
+        (symbol_short!("start"),),
+        true,
+    );
+    true
+}
+
+/// Hand the turn to `player`.
+pub fn pass_turn(game: &GameStore, player: &Address) -> Result<(), Error> {
+    let (p1, _) = players(game)?;
+    let phase = if player == &p1 { GamePhase::Player1Turn } else { GamePhase::Player2Turn };
+    set_phase(game, phase);
+    switch_clock(game, Some(player));
+    Ok(())
+}
+
+/// Move the session to `phase`, giving it a fresh deadline if the session
+/// has a turn timeout. A finished session has none.
+pub fn set_phase(game: &GameStore, phase: GamePhase) {
+    let timeout: u32 = game.get(SessionKey::TurnTimeout).unwrap_or(0);
+    if timeout == 0 || phase == GamePhase::Finished {
+        game.remove(SessionKey::Deadline);
+    } else {
+        game.set(SessionKey::Deadline, &(game.env().ledger().sequence() + timeout));
+    }
+    game.set(SessionKey::Phase, &phase);
+}
+
+/// Give each phase `ledgers` ledgers before it times out (0 = never),
+/// starting with the current one.
+pub fn set_turn_timeout(game: &GameStore, ledgers: u32) -> Result<(), Error> {
+    game.set(SessionKey::TurnTimeout, &ledgers);
+    set_phase(game, phase(game)?);
+    Ok(())
+}
+
+/// Ledger by which the current phase's move is due, if it has a deadline.
+pub fn deadline(game: &GameStore) -> Option<u32> {
+    game.get(SessionKey::Deadline)
+}
+
+/// Whether the current phase's deadline has passed.
+pub fn timed_out(game: &GameStore) -> bool {
+    deadline(game).is_some_and(|deadline| game.env().ledger().sequence() > deadline)
+}
+
+/// Give each player a clock of `ledgers` ledgers for the whole game
+/// (0 = untimed).
+pub fn set_time_budget(game: &GameStore, ledgers: u32) {
+    game.set(SessionKey::TimeBudget, &ledgers);
+}
+
+/// Stop the running clock, charging its player for the ledgers since it
+/// started, and start `player`'s, if any. Does nothing in untimed sessions.
+pub fn switch_clock(game: &GameStore, player: Option<&Address>) {
+    if game.get::<u32>(SessionKey::TimeBudget).unwrap_or(0) == 0 {
+        return;
+    }
+    let now = game.env().ledger().sequence();
+    if let Some((running, since)) = game.get::<(Address, u32)>(SessionKey::RunningClock) {
+        let used: u32 = game.get(SessionKey::ClockUsed(running.clone())).unwrap_or(0);
+        game.set(SessionKey::ClockUsed(running), &(used + now - since));
+    }
+    match player {
+        Some(player) => game.set(SessionKey::RunningClock, &(player.clone(), now)),
+        None => game.remove(SessionKey::RunningClock),
+    }
+}
+
+/// `player`'s chess clock.
+pub fn clock(game: &GameStore, player: &Address) -> Clock {
+    let mut used: u32 = game.get(SessionKey::ClockUsed(player.clone())).unwrap_or(0);
+    let running = match game.get::<(Address, u32)>(SessionKey::RunningClock) {
+        Some((running, since)) if &running == player => {
+            used += game.env().ledger().sequence() - since;
+            true
+        }
+        _ => false,
+    };
+    Clock { budget: game.get(SessionKey::TimeBudget).unwrap_or(0), used, running }
+}
+
+/// Whether `player` has used more than their clock holds.
+pub fn clock_expired(game: &GameStore, player: &Address) -> bool {
+    let clock = clock(game, player);
+    clock.budget > 0 && clock.used > clock.budget
+}
+
+/// Register the session on the hub. Callable once play has started; a game
+/// that already finished has its result reported in the same call.
+pub fn register(game: &GameStore, scores: &Scores) -> Result<(), Error> {
+    let env = game.env();
+    let phase = phase(game)?;
+    // A session cancelled before play never reaches the hub
+    let cancelled = game.get::<AbortReason>(SessionKey::Aborted) == Some(AbortReason::Cancelled);
+    if phase == GamePhase::WaitingForCommits || cancelled {
+        return Err(Error::InvalidPhase);
+    }
+
+    let registered: bool = game.get(SessionKey::HubRegistered).unwrap_or(false);
+    if registered {
+        return Err(Error::AlreadyRegistered);
+    }
+
+    hub::report_start(game, scores);
+    game.set(SessionKey::HubRegistered, &true);
+
+    // A game that finished before registration still owes the hub a result,
+    // unless it is held for `settle`, which reports it
+    if phase == GamePhase::Finished && settle_deadline(game).is_none() {
+        hub::report_end(game, &outcome(game), scores);
+    }
+
+    log!(env, "Session {} registered with hub", game.session_id());
+    env.events().publish(
+        (symbol_short!("hub_reg"),),
+        game.session_id(),
+    );
+    Ok(())
+}
+
+/// End the session with `winner`: release the pot, notify the hub (deferred
+/// to registration if not yet registered) and publish the result. `scores`
+/// is evaluated after the winner is recorded, so it can award a win bonus.
+pub fn finish<F>(game: &GameStore, winner: &Address, scores: F)
+where
+    F: FnOnce(&GameStore) -> Scores,
+{
+    let env = game.env();
+    game.set(SessionKey::Winner, winner);
+    let settled = close(game, scores);
+
+    log!(env, "Game over! Winner declared");
+    env.events().publish(
+        (symbol_short!("winner"),),
+        winner.clone(),
+    );
+    if let Some((outcome, scores)) = settled {
+        events::publish_result(game, &outcome, &scores);
+    }
+}
+
+/// End the session with neither player winning: refund both stakes, then
+/// notify the hub and publish the result as `finish` does.
+pub fn finish_draw<F>(game: &GameStore, scores: F)
+where
+    F: FnOnce(&GameStore) -> Scores,
+{
+    let env = game.env();
+    let settled = close(game, scores);
+
+    log!(env, "Game over! Drawn");
+    env.events().publish(
+        (symbol_short!("draw"),),
+        game.session_id(),
+    );
+    if let Some((outcome, scores)) = settled {
+        events::publish_result(game, &outcome, &scores);
+    }
+}
+
+/// End the session without a result, for `reason`: refund both stakes,
+/// notify the hub if the session was registered, and publish the result.
+pub fn abort<F>(game: &GameStore, reason: AbortReason, scores: F)
+where
+    F: FnOnce(&GameStore) -> Scores,
+{
+    let env = game.env();
+    game.set(SessionKey::Aborted, &reason);
+    // An aborted session has no result to hold
+    game.remove(SessionKey::SettleWindow);
+    let (outcome, scores) = close(game, scores).unwrap();
+
+    log!(env, "Game aborted");
+    env.events().publish(
+        (symbol_short!("aborted"),),
+        (game.session_id(), reason),
+    );
+    events::publish_result(game, &outcome, &scores);
+}
+
+/// How a finished session ended: aborted if it was, else won by the
+/// recorded winner, else drawn.
+pub fn outcome(game: &GameStore) -> SessionOutcome {
+    if let Some(reason) = game.get::<AbortReason>(SessionKey::Aborted) {
+        return SessionOutcome::Aborted(reason);
+    }
+    match game.get::<Address>(SessionKey::Winner) {
+        Some(winner) => SessionOutcome::Won(winner),
+        None => SessionOutcome::Draw,
+    }
+}
+
+/// Hold the session's settlement for `window` ledgers once it finishes with
+/// a result: the pot stays in escrow and the hub waits until `settle`, so a
+/// post-game audit can still change the result. An abort is settled at once.
+pub fn hold_settlement(game: &GameStore, window: u32) {
+    game.set(SessionKey::SettleWindow, &window);
+}
+
+/// Last ledger a finished session's held settlement waits for; `None` if
+/// it was never held or has been settled.
+pub fn settle_deadline(game: &GameStore) -> Option<u32> {
+    game.get(SessionKey::SettleDeadline)
+}
+
+/// Settle a finished session whose settlement was held, with `winner` as
+/// its final result (`None` for a draw): release the pot, notify the hub
+/// and publish the result, as `finish` does for a session not held.
+pub fn settle<F>(game: &GameStore, winner: Option<&Address>, scores: F) -> Result<(), Error>
+where
+    F: FnOnce(&GameStore) -> Scores,
+{
+    if settle_deadline(game).is_none() {
+        return Err(Error::InvalidPhase);
+    }
+    game.remove(SessionKey::SettleDeadline);
+    match winner {
+        Some(winner) => game.set(SessionKey::Winner, winner),
+        None => game.remove(SessionKey::Winner),
+    }
+    let (outcome, scores) = pay_and_report(game, scores);
+
+    log!(game.env(), "Game settled");
+    events::publish_result(game, &outcome, &scores);
+    Ok(())
+}
+
+/// Finish the session with the outcome recorded, settling the wager and
+/// reporting to the hub, unless its settlement is held. Returns the outcome
+/// and final scores if it was settled.
+fn close<F>(game: &GameStore, scores: F) -> Option<(SessionOutcome, Scores)>
+where
+    F: FnOnce(&GameStore) -> Scores,
+{
+    let env = game.env();
+    set_phase(game, GamePhase::Finished);
+    switch_clock(game, None);
+    snapshot::forget(game);
+    count(env, DataKey::OpenSessions, -1);
+    if game.has(SessionKey::StartedAt) {
+        count(env, DataKey::SessionsInPlay, -1);
+    }
+
+    if let Some(window) = game.get::<u32>(SessionKey::SettleWindow) {
+        game.set(SessionKey::SettleDeadline, &(env.ledger().sequence() + window));
+        return None;
+    }
+    Some(pay_and_report(game, scores))
+}
+
+/// Settle the wager on the outcome recorded and report it to the hub.
+fn pay_and_report<F>(game: &GameStore, scores: F) -> (SessionOutcome, Scores)
+where
+    F: FnOnce(&GameStore) -> Scores,
+{
+    let (p1, p2) = players(game).unwrap();
+    let outcome = outcome(game);
+    match &outcome {
+        // Release the pot, if the game was staked
+        SessionOutcome::Won(winner) => wager::settle_winner(game, winner, &p1, &p2),
+        _ => wager::settle_draw(game, &p1, &p2),
+    }
+
+    let scores = scores(game);
+    hub::report_end(game, &outcome, &scores);
+    (outcome, scores)
+}
+
+/// Game name and variant recorded at `init`.
+pub fn info(game: &GameStore) -> GameInfo {
+    game.get(SessionKey::Info).unwrap()
+}
+
+/// Extend the session's TTL to approximately 30 days.
+pub fn extend_ttl(game: &GameStore) {
+    game.extend_ttl(SessionKey::Phase, SESSION_TTL);
+}
+
+/// Sessions created on this contract and not yet finished.
+pub fn open_sessions(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::OpenSessions).unwrap_or(0)
+}
+
+/// Sessions started on this contract and not yet finished.
+pub fn sessions_in_play(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::SessionsInPlay).unwrap_or(0)
+}
+
+/// Whether a session is open, in progress or finished.
+pub fn status(game: &GameStore) -> Result<SessionStatus, Error> {
+    Ok(match phase(game)? {
+        GamePhase::WaitingForCommits => SessionStatus::Open,
+        GamePhase::Finished => SessionStatus::Finished,
+        _ => SessionStatus::InProgress,
+    })
+}
+
+/// The sessions among the `limit` created from the `offset`th on, in
+/// creation order, that have status `filter`, if given. Only those `limit`
+/// index entries are read, so a filtered page can come back short or empty
+/// before the end; the next page starts at `offset + limit`. Sessions whose
+/// storage has expired are skipped. The entries read are kept alive for
+/// `SESSION_TTL`, so a page once listed stays readable.
+pub fn list(env: &Env, filter: Option<SessionStatus>, offset: u32, limit: u32) -> Vec<SessionSummary> {
+    let created: u32 = env.storage().instance().get(&DataKey::SessionCount).unwrap_or(0);
+    let mut sessions = Vec::new(env);
+    for at in offset..created.min(offset.saturating_add(limit)) {
+        let session_id: Option<u32> = renewed(env, &DataKey::SessionAt(at));
+        let Some(game) = session_id.and_then(|id| GameStore::open(env, id).ok()) else {
+            continue;
+        };
+        let (Ok(status), Ok((player1, player2))) = (status(&game), players(&game)) else {
+            continue;
+        };
+        if filter.is_some_and(|filter| filter != status) {
+            continue;
+        }
+        sessions.push_back(SessionSummary { session_id: game.session_id(), player1, player2, status });
+    }
+    sessions
+}
+
+/// How many sessions `player` was seated in.
+pub fn session_count_for(env: &Env, player: &Address) -> u32 {
+    env.storage().persistent().get(&DataKey::PlayerSessionCount(player.clone())).unwrap_or(0)
+}
+
+/// Ids of at most `limit` of the sessions `player` was seated in, oldest
+/// first, from their `offset`th on. Like `list`, keeps the entries read
+/// alive.
+pub fn sessions_for(env: &Env, player: &Address, offset: u32, limit: u32) -> Vec<u32> {
+    let count: u32 = renewed(env, &DataKey::PlayerSessionCount(player.clone())).unwrap_or(0);
+    let mut sessions = Vec::new(env);
+    for at in offset..count.min(offset.saturating_add(limit)) {
+        if let Some(session_id) = renewed(env, &DataKey::PlayerSessionAt(player.clone(), at)) {
+            sessions.push_back(session_id);
+        }
+    }
+    sessions
+}
+
+/// Add a new session to the contract's index.
+fn index(game: &GameStore) {
+    let env = game.env();
+    let created: u32 = env.storage().instance().get(&DataKey::SessionCount).unwrap_or(0);
+    let key = DataKey::SessionAt(created);
+    env.storage().persistent().set(&key, &game.session_id());
+    env.storage().persistent().extend_ttl(&key, SESSION_TTL, SESSION_TTL);
+    env.storage().instance().set(&DataKey::SessionCount, &(created + 1));
+}
+
+/// Add the session to the index of those `player` was seated in.
+pub fn index_player(game: &GameStore, player: &Address) {
+    let env = game.env();
+    let seated = session_count_for(env, player);
+    let key = DataKey::PlayerSessionAt(player.clone(), seated);
+    env.storage().persistent().set(&key, &game.session_id());
+    env.storage().persistent().extend_ttl(&key, SESSION_TTL, SESSION_TTL);
+    let key = DataKey::PlayerSessionCount(player.clone());
+    env.storage().persistent().set(&key, &(seated + 1));
+    env.storage().persistent().extend_ttl(&key, SESSION_TTL, SESSION_TTL);
+}
+
+/// Read the index entry `key`, extending it to `SESSION_TTL` if present.
+fn renewed<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        env.storage().persistent().extend_ttl(key, SESSION_TTL, SESSION_TTL);
+    }
+    value
+}
+
+/// Move the session counter `key` by `delta`.
+fn count(env: &Env, key: DataKey, delta: i32) {
+    let sessions: u32 = env.storage().instance().get(&key).unwrap_or(0);
+    env.storage().instance().set(&key, &sessions.saturating_add_signed(delta));
+}
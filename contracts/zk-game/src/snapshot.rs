@@ -0,0 +1,87 @@
+// ============================================================================
+// Session Snapshots
+// ============================================================================
+// A session kept in temporary storage is gone for good once its TTL lapses,
+// even mid-match. A snapshot captures every entry of a session still being
+// played; exporting one records its digest, and a session whose storage has
+// lapsed can be restored from the last snapshot exported for it. The game
+// decides who must consent to a restore.
+//
+// Only the last export's digest is kept, so an older snapshot cannot roll a
+// session back, and finishing a session forgets it, so a settled game cannot
+// be restored and settled again.
+
+use soroban_sdk::{contracttype, xdr::ToXdr, BytesN, Env, IntoVal, TryFromVal, Val, Vec};
+
+use crate::session::{self, GamePhase, SESSION_TTL};
+use crate::storage::{GameStore, SessionKey, StorageTier};
+use crate::{DataKey, Error};
+
+/// Every entry of a session at the time it was exported.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameSnapshot {
+    pub session_id: u32,
+    /// Storage tier the session's entries live in
+    pub tier: StorageTier,
+    /// Entries as (key, value), keys without the session namespace
+    pub entries: Vec<(Val, Val)>,
+}
+
+/// Snapshot `game`, framework entries and the game's own `game_keys` alike,
+/// and record it as the one the session can be restored from.
+pub fn export<K: IntoVal<Env, Val> + TryFromVal<Env, Val> + Clone>(
+    game: &GameStore,
+    game_keys: &Vec<K>,
+) -> Result<GameSnapshot, Error> {
+    let env = game.env();
+    if session::phase(game)? == GamePhase::Finished {
+        return Err(Error::InvalidPhase);
+    }
+    let mut entries = Vec::new(env);
+    for key in game_keys.iter() {
+        let key: Val = key.into_val(env);
+        if let Some(value) = game.get::<Val>(key) {
+            entries.push_back((key, value));
+        }
+    }
+    for key in game.keys().iter() {
+        let key: Val = key.into_val(env);
+        if let Some(value) = game.get::<Val>(key) {
+            entries.push_back((key, value));
+        }
+    }
+    let snapshot = GameSnapshot { session_id: game.session_id(), tier: game.tier(), entries };
+
+    let record = DataKey::Snapshot(game.session_id());
+    env.storage().persistent().set(&record, &digest(env, &snapshot));
+    env.storage().persistent().extend_ttl(&record, SESSION_TTL, SESSION_TTL);
+    Ok(snapshot)
+}
+
+/// sha256 of `snapshot`'s XDR.
+pub fn digest(env: &Env, snapshot: &GameSnapshot) -> BytesN<32> {
+    env.crypto().sha256(&snapshot.clone().to_xdr(env)).to_bytes()
+}
+
+/// Write `snapshot` back, if its session's storage has lapsed and it is the
+/// last snapshot exported for it. Returns the restored session.
+pub fn restore<'a>(env: &'a Env, snapshot: &GameSnapshot) -> Result<GameStore<'a>, Error> {
+    if GameStore::new(env, snapshot.session_id).has(SessionKey::Phase) {
+        return Err(Error::InvalidPhase);
+    }
+    let recorded: Option<BytesN<32>> = env.storage().persistent().get(&DataKey::Snapshot(snapshot.session_id));
+    if recorded != Some(digest(env, snapshot)) {
+        return Err(Error::SnapshotMismatch);
+    }
+    let game = GameStore::create(env, snapshot.session_id, snapshot.tier);
+    for (key, value) in snapshot.entries.iter() {
+        game.set(key, &value);
+    }
+    Ok(game)
+}
+
+/// Forget the snapshot `game` could be restored from.
+pub fn forget(game: &GameStore) {
+    game.env().storage().persistent().remove(&DataKey::Snapshot(game.session_id()));
+}
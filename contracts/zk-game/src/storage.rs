@@ -0,0 +1,318 @@
+// ============================================================================
+// Per-Game Storage Namespace
+// ============================================================================
+// Every entry belonging to a game lives under `("Game", session_id, key)`,
+// so the entries of concurrent games never share a key and one game can be
+// enumerated or cleared without touching any other.
+//
+// The framework's own entries use `SessionKey`; games add their own key enum
+// (any `#[contracttype]`) for rule-specific state.
+//
+// A game's entries are temporary by default, the cheapest tier, and are gone
+// for good once their TTL lapses. Games expected to outlive it can be
+// created persistent instead, so an entry that lapses is archived and can be
+// restored rather than lost. The tier is chosen at creation and every entry
+// of the game is read and written through it.
+//
+// Every write keeps the entry alive for `SESSION_TTL`, so entries that move
+// with play never lapse mid-game. Those written once, when the game is set
+// up, are renewed by `extend_live`, which touches a fixed handful of keys so
+// a move's footprint does not grow with the game.
+
+use soroban_sdk::{contracttype, symbol_short, Address, Env, IntoVal, Symbol, TryFromVal, Val, Vec};
+
+use crate::session::SESSION_TTL;
+use crate::{DataKey, Error};
+
+/// Storage tier a game's entries live in, chosen when it is created.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StorageTier {
+    /// Temporary storage: cheapest, lost once the TTL lapses
+    Temporary,
+    /// Persistent storage: archived, not lost, once the TTL lapses
+    Persistent,
+}
+
+/// Storage key of one game entry: `("Game", session_id, key)`.
+pub type EntryKey = (Symbol, u32, Val);
+
+/// Keys of the entries the framework keeps for each game.
+#[contracttype]
+#[derive(Clone)]
+pub enum SessionKey {
+    /// Hub contract address
+    HubAddress,
+    /// Current game phase
+    Phase,
+    /// Player 1 address
+    Player1,
+    /// Player 2 address
+    Player2,
+    /// Address of the winner
+    Winner,
+    /// Whether the session has been registered with the hub
+    HubRegistered,
+    /// Hub interface version negotiated at initialise
+    HubVersion,
+    /// Hub interface the game reports to
+    HubInterface,
+    /// Game name and rule variant
+    Info,
+    /// Wager terms, if the game is staked
+    Wager,
+    /// Whether a player has deposited their stake
+    Deposited(Address),
+    /// Payout refused by the token at settlement, awaiting withdrawal
+    HeldPayout(Address),
+    /// Stake this game holds in escrow and has yet to pay out
+    Escrowed,
+    /// Ledger timestamp at which play started
+    StartedAt,
+    /// Signable attestation of the result
+    Attestation,
+    /// Custodial sub-identifiers of both seats, see `identity`
+    Tags,
+    /// Account behind a tagged seat, see `identity`
+    Account(Address),
+    /// Bond terms, if players posted bonds, see `bond`
+    Bond,
+    /// Bond a player has in escrow
+    Bonded(Address),
+    /// Ledgers each phase may last before the player it waits on forfeits
+    TurnTimeout,
+    /// Ledger by which the current phase's move is due
+    Deadline,
+    /// Ledgers each player's clock holds for the whole game
+    TimeBudget,
+    /// Ledgers a player's clock has used, excluding the running move
+    ClockUsed(Address),
+    /// Player whose clock is running, and the ledger it started at
+    RunningClock,
+    /// Teammate sharing a seat's board in team games, see `team`
+    Teammate(Address),
+    /// sha256 of a player's coin-flip nonce, see `randomness`
+    CoinCommit(Address),
+    /// A player's revealed coin-flip nonce
+    CoinNonce(Address),
+    /// Last ledger the second coin-flip nonce may be revealed in
+    CoinDeadline,
+    /// Player who forfeited the coin flip by not revealing in time
+    CoinForfeit,
+    /// Why the session ended without a result, if it did
+    Aborted,
+    /// Ledgers a finished session's settlement is held for, see `session`
+    SettleWindow,
+    /// Last ledger a finished session's held settlement waits for
+    SettleDeadline,
+    /// Verifier the game's proofs are checked by, see `verifier::pin`
+    Verifier,
+    /// Verification key ids the game pinned, by circuit
+    Keys,
+}
+
+/// Handle on the storage entries of a single game session.
+pub struct GameStore<'a> {
+    env: &'a Env,
+    session_id: u32,
+    tier: StorageTier,
+}
+
+impl<'a> GameStore<'a> {
+    pub fn new(env: &'a Env, session_id: u32) -> Self {
+        let tier = if env.storage().persistent().has(&DataKey::PersistentSession(session_id)) {
+            StorageTier::Persistent
+        } else {
+            StorageTier::Temporary
+        };
+        Self { env, session_id, tier }
+    }
+
+    /// Handle on game `session_id` being created, whose entries from now on
+    /// live in `tier`.
+    pub fn create(env: &'a Env, session_id: u32, tier: StorageTier) -> Self {
+        let marker = DataKey::PersistentSession(session_id);
+        match tier {
+            StorageTier::Persistent => env.storage().persistent().set(&marker, &true),
+            StorageTier::Temporary => env.storage().persistent().remove(&marker),
+        }
+        Self { env, session_id, tier }
+    }
+
+    /// Open game `session_id`, failing if no such game was created.
+    pub fn open(env: &'a Env, session_id: u32) -> Result<Self, Error> {
+        let game = Self::new(env, session_id);
+        if !game.has(SessionKey::Phase) {
+            return Err(Error::NotInitialized);
+        }
+        Ok(game)
+    }
+
+    pub fn env(&self) -> &'a Env {
+        self.env
+    }
+
+    pub fn session_id(&self) -> u32 {
+        self.session_id
+    }
+
+    pub fn tier(&self) -> StorageTier {
+        self.tier
+    }
+
+    /// Full storage key of a game entry.
+    pub fn key(&self, key: impl IntoVal<Env, Val>) -> EntryKey {
+        (symbol_short!("Game"), self.session_id, key.into_val(self.env))
+    }
+
+    pub fn get<V: TryFromVal<Env, Val>>(&self, key: impl IntoVal<Env, Val>) -> Option<V> {
+        let key = self.key(key);
+        match self.tier {
+            StorageTier::Temporary => self.env.storage().temporary().get(&key),
+            StorageTier::Persistent => self.env.storage().persistent().get(&key),
+        }
+    }
+
+    /// Write a game entry, keeping it alive for `SESSION_TTL`.
+    pub fn set<V: IntoVal<Env, Val>>(&self, key: impl IntoVal<Env, Val>, val: &V) {
+        let key: Val = key.into_val(self.env);
+        let entry = self.key(key);
+        match self.tier {
+            StorageTier::Temporary => self.env.storage().temporary().set(&entry, val),
+            StorageTier::Persistent => self.env.storage().persistent().set(&entry, val),
+        }
+        self.extend_ttl(key, SESSION_TTL);
+    }
+
+    pub fn has(&self, key: impl IntoVal<Env, Val>) -> bool {
+        let key = self.key(key);
+        match self.tier {
+            StorageTier::Temporary => self.env.storage().temporary().has(&key),
+            StorageTier::Persistent => self.env.storage().persistent().has(&key),
+        }
+    }
+
+    pub fn remove(&self, key: impl IntoVal<Env, Val>) {
+        let key = self.key(key);
+        match self.tier {
+            StorageTier::Temporary => self.env.storage().temporary().remove(&key),
+            StorageTier::Persistent => self.env.storage().persistent().remove(&key),
+        }
+    }
+
+    /// Extend a game entry's TTL to `ledgers`. A persistent game's tier
+    /// marker is kept alive with it.
+    pub fn extend_ttl(&self, key: impl IntoVal<Env, Val>, ledgers: u32) {
+        let key = self.key(key);
+        match self.tier {
+            StorageTier::Temporary => self.env.storage().temporary().extend_ttl(&key, ledgers, ledgers),
+            StorageTier::Persistent => {
+                let persistent = self.env.storage().persistent();
+                persistent.extend_ttl(&key, ledgers, ledgers);
+                persistent.extend_ttl(&DataKey::PersistentSession(self.session_id), ledgers, ledgers);
+            }
+        }
+    }
+
+    /// Enumerate every framework key this game may have written. The set is
+    /// derived from the key schema and the two player addresses, so it costs
+    /// O(entries-in-one-game) regardless of how many other games exist.
+    pub fn keys(&self) -> Vec<SessionKey> {
+        let mut keys = Vec::from_array(self.env, [
+            SessionKey::HubAddress,
+            SessionKey::Phase,
+            SessionKey::Player1,
+            SessionKey::Player2,
+            SessionKey::Winner,
+            SessionKey::HubRegistered,
+            SessionKey::HubVersion,
+            SessionKey::HubInterface,
+            SessionKey::Info,
+            SessionKey::Wager,
+            SessionKey::Escrowed,
+            SessionKey::StartedAt,
+            SessionKey::Attestation,
+            SessionKey::Tags,
+            SessionKey::Bond,
+            SessionKey::TurnTimeout,
+            SessionKey::Deadline,
+            SessionKey::TimeBudget,
+            SessionKey::RunningClock,
+            SessionKey::CoinDeadline,
+            SessionKey::CoinForfeit,
+            SessionKey::Aborted,
+            SessionKey::SettleWindow,
+            SessionKey::SettleDeadline,
+            SessionKey::Verifier,
+            SessionKey::Keys,
+        ]);
+        let players: [Option<Address>; 2] = [self.get(SessionKey::Player1), self.get(SessionKey::Player2)];
+        for player in players.into_iter().flatten() {
+            keys.push_back(SessionKey::Deposited(player.clone()));
+            keys.push_back(SessionKey::HeldPayout(player.clone()));
+            keys.push_back(SessionKey::Bonded(player.clone()));
+            keys.push_back(SessionKey::ClockUsed(player.clone()));
+            keys.push_back(SessionKey::Teammate(player.clone()));
+            keys.push_back(SessionKey::CoinCommit(player.clone()));
+            keys.push_back(SessionKey::CoinNonce(player.clone()));
+            keys.push_back(SessionKey::Account(player));
+        }
+        keys
+    }
+
+    /// Framework keys written when a game is set up and rarely after, which
+    /// live as long as the game: its seats, hub, terms and pinned keys.
+    pub fn live_keys(&self) -> Vec<SessionKey> {
+        let mut keys = Vec::from_array(self.env, [
+            SessionKey::HubAddress,
+            SessionKey::Phase,
+            SessionKey::Player1,
+            SessionKey::Player2,
+            SessionKey::HubVersion,
+            SessionKey::HubInterface,
+            SessionKey::Info,
+            SessionKey::Wager,
+            SessionKey::Escrowed,
+            SessionKey::Tags,
+            SessionKey::Bond,
+            SessionKey::TurnTimeout,
+            SessionKey::TimeBudget,
+            SessionKey::Verifier,
+            SessionKey::Keys,
+        ]);
+        let players: [Option<Address>; 2] = [self.get(SessionKey::Player1), self.get(SessionKey::Player2)];
+        for player in players.into_iter().flatten() {
+            keys.push_back(SessionKey::Deposited(player.clone()));
+            keys.push_back(SessionKey::Bonded(player.clone()));
+            keys.push_back(SessionKey::Account(player));
+        }
+        keys
+    }
+
+    /// Extend the TTL of the framework's `live_keys` and the game's own
+    /// `game_keys`, those written at set-up, to `ledgers`, so they last as
+    /// long as the entries play keeps rewriting.
+    pub fn extend_live<K: IntoVal<Env, Val> + TryFromVal<Env, Val> + Clone>(&self, game_keys: &Vec<K>, ledgers: u32) {
+        for key in game_keys.iter() {
+            if self.has(key.clone()) {
+                self.extend_ttl(key, ledgers);
+            }
+        }
+        for key in self.live_keys().iter() {
+            if self.has(key.clone()) {
+                self.extend_ttl(key, ledgers);
+            }
+        }
+    }
+
+    /// Remove every framework entry of this game, plus the game's own
+    /// `game_keys`.
+    pub fn clear<K: IntoVal<Env, Val> + TryFromVal<Env, Val> + Clone>(&self, game_keys: &Vec<K>) {
+        for key in game_keys.iter() {
+            self.remove(key);
+        }
+        for key in self.keys().iter() {
+            self.remove(key);
+        }
+    }
+}
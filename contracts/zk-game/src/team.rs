@@ -0,0 +1,58 @@
+// ============================================================================
+// Teams
+// ============================================================================
+// A seat is normally held by one player. In team play each seat gains a
+// teammate who shares its board and may act for it: fire on the team's turn
+// and answer the shots at its board. The seat holder's address still keys
+// all game state, so a game maps whoever acts to their seat and plays on.
+
+use soroban_sdk::{symbol_short, vec, Address, Vec};
+
+use crate::session;
+use crate::storage::{GameStore, SessionKey};
+use crate::Error;
+
+/// Give each seat a teammate. All four players must be distinct.
+pub fn register(game: &GameStore, teammate1: &Address, teammate2: &Address) -> Result<(), Error> {
+    let (p1, p2) = session::players(game)?;
+    if teammate1 == teammate2 || [&p1, &p2].iter().any(|seat| *seat == teammate1 || *seat == teammate2) {
+        return Err(Error::DuplicatePlayer);
+    }
+    game.set(SessionKey::Teammate(p1.clone()), teammate1);
+    game.set(SessionKey::Teammate(p2.clone()), teammate2);
+    game.env().events().publish(
+        (symbol_short!("teams"), game.session_id()),
+        ((p1, teammate1.clone()), (p2, teammate2.clone())),
+    );
+    Ok(())
+}
+
+/// Teammate of the seat held by `seat`, in team games.
+pub fn teammate(game: &GameStore, seat: &Address) -> Option<Address> {
+    game.get(SessionKey::Teammate(seat.clone()))
+}
+
+/// The seat `member` acts for: their teammate's, if they joined as one,
+/// otherwise their own address.
+pub fn seat(game: &GameStore, member: &Address) -> Address {
+    let Ok((p1, p2)) = session::players(game) else {
+        return member.clone();
+    };
+    [p1, p2]
+        .into_iter()
+        .find(|seat| teammate(game, seat).as_ref() == Some(member))
+        .unwrap_or_else(|| member.clone())
+}
+
+/// Everyone playing: both seats, then their teammates in seat order.
+pub fn members(game: &GameStore) -> Vec<Address> {
+    let env = game.env();
+    let (p1, p2) = session::players(game).unwrap();
+    let mut members = vec![env, p1.clone(), p2.clone()];
+    for seat in [p1, p2] {
+        if let Some(teammate) = teammate(game, &seat) {
+            members.push_back(teammate);
+        }
+    }
+    members
+}
@@ -0,0 +1,272 @@
+// ============================================================================
+// Proof Verifier
+// ============================================================================
+// Proofs are checked by a standalone verifier contract (`contracts/verifier`)
+// that any Stellar Game Studio game can share. Verification keys are
+// registered with it once and named by the hash of their contents, so a key
+// can never change under the games using it; a game records which key each
+// of its circuits uses, and rotates one by pointing at a new key id.
+// Rotation is timelocked: a replacement key is proposed, and can only be
+// activated `KEY_UPGRADE_DELAY` ledgers later, so players see it coming.
+// Each game pins the verifier and keys in force when it was created (see
+// `pin`), so no game is ever judged by a key swapped in mid-play, and no
+// game, however long it stays open, holds a rotation up.
+//
+// Circuits that change between games go through the circuit registry
+// instead: each registered version fixes its key, proof system and public
+// input layout for good, and a game pins the version it was initialised
+// with, so games started before an upgrade finish on the circuit they began.
+//
+// Keys for circuits proven over BLS12-381 are registered separately, as
+// `BlsVerificationKey`s; a proof's system decides which kind its key id
+// names.
+
+use soroban_sdk::{contractclient, contracterror, contracttype, xdr::ToXdr, Address, BytesN, Env, Map, Symbol, Vec};
+
+use crate::bn254::Fr;
+use crate::groth16::VerificationKey;
+use crate::groth16_bls::BlsVerificationKey;
+use crate::proof::{Proof, ProofSystem};
+use crate::admin::{self, Service};
+use crate::storage::{GameStore, SessionKey};
+use crate::{DataKey, Error};
+
+/// Most public inputs a verifier call may carry.
+pub const MAX_PUBLIC_INPUTS: u32 = 16;
+
+/// Ledgers a proposed key waits before it can replace a circuit's key
+/// (about a day)
+pub const KEY_UPGRADE_DELAY: u32 = 17_280;
+
+/// Errors raised by the verifier contract.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum VerifierError {
+    /// No key registered under this id
+    KeyNotFound = 1,
+    /// Verification key has a malformed point or no constant term
+    InvalidVerificationKey = 2,
+    /// Public input is not a field element, or there are too many
+    InvalidPublicInput = 3,
+    /// Proof exceeds `proof::MAX_PROOF_SIZE`
+    ProofTooLarge = 4,
+    /// No verifier for the proof's system
+    UnsupportedProofSystem = 5,
+}
+
+/// Shared on-chain proof verifier.
+#[contractclient(name = "VerifierClient")]
+pub trait Verifier {
+    /// Register `vk` and return its id, the SHA-256 of its XDR encoding.
+    /// Registering a key again returns the same id.
+    fn register_key(env: Env, vk: VerificationKey) -> Result<BytesN<32>, VerifierError>;
+
+    /// The key registered under `vk_id`, if any.
+    fn get_key(env: Env, vk_id: BytesN<32>) -> Option<VerificationKey>;
+
+    /// Register a BLS12-381 key, as `register_key` does a BN254 one.
+    fn register_bls_key(env: Env, vk: BlsVerificationKey) -> Result<BytesN<32>, VerifierError>;
+
+    /// The BLS12-381 key registered under `vk_id`, if any.
+    fn get_bls_key(env: Env, vk_id: BytesN<32>) -> Option<BlsVerificationKey>;
+
+    /// Whether `proof` is valid for `public_inputs` (big-endian field
+    /// elements) under the key `vk_id`, a BLS12-381 key for BLS12-381
+    /// proofs.
+    fn verify(
+        env: Env,
+        vk_id: BytesN<32>,
+        public_inputs: Vec<BytesN<32>>,
+        proof: Proof,
+    ) -> Result<bool, VerifierError>;
+}
+
+/// One registered version of a circuit.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircuitVersion {
+    /// Verifier key id the proofs are checked against
+    pub vk_id: BytesN<32>,
+    /// System the proofs are made with
+    pub proof_system: ProofSystem,
+    /// Names of the public inputs in circuit order, as the game defines them
+    pub public_inputs: Vec<Symbol>,
+}
+
+/// A key proposed to replace a circuit's.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyUpgrade {
+    /// Verifier key id that will replace the circuit's
+    pub vk_id: BytesN<32>,
+    /// First ledger the upgrade can be activated in
+    pub activates_at: u32,
+}
+
+/// The id a verifier registers `vk` under: the SHA-256 of its XDR encoding.
+pub fn vk_id(env: &Env, vk: &VerificationKey) -> BytesN<32> {
+    env.crypto().sha256(&vk.clone().to_xdr(env)).into()
+}
+
+/// The id a verifier registers the BLS12-381 key `vk` under, as `vk_id`.
+pub fn bls_vk_id(env: &Env, vk: &BlsVerificationKey) -> BytesN<32> {
+    env.crypto().sha256(&vk.clone().to_xdr(env)).into()
+}
+
+/// Verifier configured for this deployment, if any.
+pub fn verifier(env: &Env) -> Option<Address> {
+    admin::service(env, Service::Verifier)
+}
+
+/// Id of the key `circuit` is verified with, if one is set.
+pub fn key_id(env: &Env, circuit: &Symbol) -> Option<BytesN<32>> {
+    env.storage().instance().get(&DataKey::VerificationKey(circuit.clone()))
+}
+
+/// Verify `circuit` with the verifier's key `vk_id`, if it has no key yet.
+/// Admin only. Games created before it pinned no key for the circuit, so
+/// take this one. Replacing a key goes through `propose_key_id` and
+/// `activate_key_id`.
+pub fn set_key_id(env: &Env, circuit: &Symbol, vk_id: &BytesN<32>) -> Result<(), Error> {
+    admin::require_admin(env)?;
+    if key_id(env, circuit).is_some() {
+        return Err(Error::KeyAlreadySet);
+    }
+    require_registered_key(env, vk_id)?;
+    env.storage().instance().set(&DataKey::VerificationKey(circuit.clone()), vk_id);
+    Ok(())
+}
+
+/// Propose the verifier's key `vk_id` to replace `circuit`'s, e.g. after
+/// the circuit is recompiled; it can be activated `KEY_UPGRADE_DELAY`
+/// ledgers from now. Admin only. A new proposal replaces the last one and
+/// restarts the delay.
+pub fn propose_key_id(env: &Env, circuit: &Symbol, vk_id: &BytesN<32>) -> Result<KeyUpgrade, Error> {
+    admin::require_admin(env)?;
+    require_registered_key(env, vk_id)?;
+    let upgrade = KeyUpgrade {
+        vk_id: vk_id.clone(),
+        activates_at: env.ledger().sequence() + KEY_UPGRADE_DELAY,
+    };
+    env.storage().instance().set(&DataKey::KeyUpgrade(circuit.clone()), &upgrade);
+    Ok(upgrade)
+}
+
+/// Replace `circuit`'s key with the proposed one once its delay has
+/// passed. Admin only. Games created from now on use the new key; those
+/// already created keep the one they pinned. Returns the new key id.
+pub fn activate_key_id(env: &Env, circuit: &Symbol) -> Result<BytesN<32>, Error> {
+    admin::require_admin(env)?;
+    let upgrade = key_upgrade(env, circuit).ok_or(Error::KeyUpgradeNotReady)?;
+    if env.ledger().sequence() < upgrade.activates_at {
+        return Err(Error::KeyUpgradeNotReady);
+    }
+    env.storage().instance().set(&DataKey::VerificationKey(circuit.clone()), &upgrade.vk_id);
+    env.storage().instance().remove(&DataKey::KeyUpgrade(circuit.clone()));
+    Ok(upgrade.vk_id)
+}
+
+/// Pin the verifier and the keys of `circuits` in force now to `game`,
+/// which is judged by them to the end, whatever is rotated meanwhile.
+/// Call when the game is created.
+pub fn pin(game: &GameStore, circuits: &[Symbol]) {
+    let env = game.env();
+    match verifier(env) {
+        Some(verifier) => game.set(SessionKey::Verifier, &verifier),
+        None => game.remove(SessionKey::Verifier),
+    }
+    let mut keys = Map::new(env);
+    for circuit in circuits {
+        if let Some(vk_id) = key_id(env, circuit) {
+            keys.set(circuit.clone(), vk_id);
+        }
+    }
+    game.set(SessionKey::Keys, &keys);
+}
+
+/// Id of the key `game` verifies `circuit` with: the one it pinned, or for
+/// a circuit first keyed since, the one set now.
+pub fn game_key_id(game: &GameStore, circuit: &Symbol) -> Option<BytesN<32>> {
+    game.get::<Map<Symbol, BytesN<32>>>(SessionKey::Keys)
+        .and_then(|keys| keys.get(circuit.clone()))
+        .or_else(|| key_id(game.env(), circuit))
+}
+
+/// The key upgrade proposed for `circuit`, if any.
+pub fn key_upgrade(env: &Env, circuit: &Symbol) -> Option<KeyUpgrade> {
+    env.storage().instance().get(&DataKey::KeyUpgrade(circuit.clone()))
+}
+
+/// The registered circuit version `circuit_id`, if any.
+pub fn circuit(env: &Env, circuit_id: &Symbol) -> Option<CircuitVersion> {
+    env.storage().instance().get(&DataKey::Circuit(circuit_id.clone()))
+}
+
+/// Register `version` as `circuit_id`. Admin only. Versions are permanent,
+/// so an id can be registered once; publish an upgrade under a new id.
+pub fn register_circuit(env: &Env, circuit_id: &Symbol, version: &CircuitVersion) -> Result<(), Error> {
+    admin::require_admin(env)?;
+    if circuit(env, circuit_id).is_some() {
+        return Err(Error::CircuitExists);
+    }
+    if version.public_inputs.len() > MAX_PUBLIC_INPUTS {
+        return Err(Error::InvalidCircuit);
+    }
+    require_registered_key(env, &version.vk_id)?;
+    env.storage().instance().set(&DataKey::Circuit(circuit_id.clone()), version);
+    Ok(())
+}
+
+fn require_registered_key(env: &Env, vk_id: &BytesN<32>) -> Result<(), Error> {
+    let verifier = verifier(env).ok_or(Error::VerifierNotSet)?;
+    let client = VerifierClient::new(env, &verifier);
+    match client.try_get_key(vk_id) {
+        Ok(Ok(Some(_))) => Ok(()),
+        Ok(Ok(None)) => match client.try_get_bls_key(vk_id) {
+            Ok(Ok(Some(_))) => Ok(()),
+            Ok(Ok(None)) => Err(Error::InvalidVerificationKey),
+            _ => Err(Error::VerifierUnavailable),
+        },
+        _ => Err(Error::VerifierUnavailable),
+    }
+}
+
+/// Whether `proof` is valid for `public_inputs` under the verifier's key
+/// `vk_id`. A proof the verifier rejects is `Ok(false)`; a verifier that
+/// is missing or cannot be called is an error, as is a proof it refuses
+/// to check at all.
+pub fn verify(env: &Env, vk_id: &BytesN<32>, public_inputs: &[Fr], proof: &Proof) -> Result<bool, Error> {
+    let verifier = verifier(env).ok_or(Error::VerifierNotSet)?;
+    verify_with(env, &verifier, vk_id, public_inputs, proof)
+}
+
+/// `verify` for a proof made in `game`, by the verifier it pinned.
+pub fn verify_in(game: &GameStore, vk_id: &BytesN<32>, public_inputs: &[Fr], proof: &Proof) -> Result<bool, Error> {
+    let env = game.env();
+    let verifier = game.get(SessionKey::Verifier).or_else(|| verifier(env)).ok_or(Error::VerifierNotSet)?;
+    verify_with(env, &verifier, vk_id, public_inputs, proof)
+}
+
+fn verify_with(
+    env: &Env,
+    verifier: &Address,
+    vk_id: &BytesN<32>,
+    public_inputs: &[Fr],
+    proof: &Proof,
+) -> Result<bool, Error> {
+    let mut inputs = Vec::new(env);
+    for input in public_inputs {
+        inputs.push_back(BytesN::from_array(env, &input.to_be_bytes()));
+    }
+    match VerifierClient::new(env, verifier).try_verify(vk_id, &inputs, proof) {
+        Ok(Ok(valid)) => Ok(valid),
+        Err(Ok(VerifierError::InvalidPublicInput)) => Ok(false),
+        Err(Ok(VerifierError::KeyNotFound | VerifierError::InvalidVerificationKey)) => {
+            Err(Error::InvalidVerificationKey)
+        }
+        Err(Ok(VerifierError::ProofTooLarge)) => Err(Error::ProofTooLarge),
+        Err(Ok(VerifierError::UnsupportedProofSystem)) => Err(Error::UnsupportedProofSystem),
+        _ => Err(Error::VerifierUnavailable),
+    }
+}
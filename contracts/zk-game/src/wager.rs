@@ -0,0 +1,286 @@
+// ============================================================================
+// Wager Escrow
+// ============================================================================
+// Optional stakes in any SEP-41 token. Each player deposits the agreed amount
+// before play starts, either by a direct authorised transfer or by approving
+// the contract, which then pulls the stake with `transfer_from` when the
+// player commits their fleet. The pot goes to the winner at settlement.
+//
+// Tokens may be clawback-enabled or have freezable trustlines, so transfers
+// are made with `try_` calls: a deposit that does not arrive in full is
+// rejected, and a payout the token refuses is held in the contract for the
+// recipient to withdraw once their account can receive it again.
+//
+// When the deployment has a price oracle, the USD rate at lock-in is recorded
+// with the terms, and USD tier limits are enforced (see `oracle`).
+
+use soroban_sdk::{contracttype, log, symbol_short, token::TokenClient, Address};
+
+use crate::oracle::{self, PriceData};
+use crate::storage::{GameStore, SessionKey};
+use crate::{identity, Error};
+
+/// Wager terms for one game.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WagerConfig {
+    /// SEP-41 token contract
+    pub token: Address,
+    /// Stake per player, in the token's smallest unit
+    pub amount: i128,
+    /// Token decimals, recorded so clients can render `amount`
+    pub decimals: u32,
+    /// USD price of the token at lock-in, in oracle decimals (0 = unpriced)
+    pub usd_price: i128,
+    /// Time of the price quote, unix seconds (0 = unpriced)
+    pub price_timestamp: u64,
+}
+
+/// Record wager terms after checking the token speaks SEP-41. If USD
+/// limits are configured the stake must be priced and within them;
+/// otherwise a price is recorded when the oracle has one.
+pub fn configure(game: &GameStore, token: &Address, amount: i128) -> Result<WagerConfig, Error> {
+    let env = game.env();
+    if amount <= 0 {
+        return Err(Error::InvalidWager);
+    }
+    let decimals = token_decimals(game, token)?;
+
+    let quote = if oracle::limits(env).is_some() {
+        Some(oracle::price(env, token)?)
+    } else {
+        oracle::price(env, token).ok()
+    };
+    if let Some(quote) = &quote {
+        oracle::check_limits(env, oracle::to_usd(amount, decimals, quote.price)?)?;
+    }
+
+    Ok(store(game, token, amount, decimals, quote))
+}
+
+/// Record wager terms from a USD value, converted at the current oracle rate.
+pub fn configure_usd(game: &GameStore, token: &Address, usd: i128) -> Result<WagerConfig, Error> {
+    let env = game.env();
+    if usd <= 0 {
+        return Err(Error::InvalidWager);
+    }
+    let decimals = token_decimals(game, token)?;
+    let quote = oracle::price(env, token)?;
+    oracle::check_limits(env, usd)?;
+
+    let amount = oracle::from_usd(usd, decimals, quote.price)?;
+    if amount <= 0 {
+        return Err(Error::InvalidWager);
+    }
+    Ok(store(game, token, amount, decimals, Some(quote)))
+}
+
+fn token_decimals(game: &GameStore, token: &Address) -> Result<u32, Error> {
+    match TokenClient::new(game.env(), token).try_decimals() {
+        Ok(Ok(decimals)) => Ok(decimals),
+        _ => Err(Error::InvalidWager),
+    }
+}
+
+fn store(game: &GameStore, token: &Address, amount: i128, decimals: u32, quote: Option<PriceData>) -> WagerConfig {
+    let (usd_price, price_timestamp) = quote.map_or((0, 0), |q| (q.price, q.timestamp));
+    let config = WagerConfig {
+        token: token.clone(),
+        amount,
+        decimals,
+        usd_price,
+        price_timestamp,
+    };
+    game.set(SessionKey::Wager, &config);
+    config
+}
+
+/// Whether any stake has been deposited yet.
+pub fn any_deposited(game: &GameStore, p1: &Address, p2: &Address) -> bool {
+    has_deposited(game, p1) || has_deposited(game, p2)
+}
+
+pub fn has_deposited(game: &GameStore, player: &Address) -> bool {
+    game.get(SessionKey::Deposited(player.clone())).unwrap_or(false)
+}
+
+/// Whether play may start: no wager, or both stakes in escrow.
+pub fn is_funded(game: &GameStore, p1: &Address, p2: &Address) -> bool {
+    if !game.has(SessionKey::Wager) {
+        return true;
+    }
+    has_deposited(game, p1) && has_deposited(game, p2)
+}
+
+/// Move `player`'s stake into escrow with a transfer they authorise.
+pub fn deposit(game: &GameStore, player: &Address) -> Result<(), Error> {
+    let account = identity::account(game, player);
+    escrow(game, player, |token, contract, amount| {
+        token.try_transfer(&account, contract, amount).is_ok_and(|r| r.is_ok())
+    })
+}
+
+/// Pull `player`'s stake into escrow from an allowance granted to this
+/// contract. An allowance below the stake (or expired) is reported as
+/// `InsufficientAllowance` rather than a generic transfer failure.
+pub fn deposit_from_allowance(game: &GameStore, player: &Address) -> Result<(), Error> {
+    let config: WagerConfig = game.get(SessionKey::Wager).ok_or(Error::InvalidWager)?;
+    if allowance(game, &config, player) < config.amount {
+        return Err(Error::InsufficientAllowance);
+    }
+    let account = identity::account(game, player);
+    escrow(game, player, |token, contract, amount| {
+        token.try_transfer_from(contract, &account, contract, amount).is_ok_and(|r| r.is_ok())
+    })
+}
+
+/// Whether `player` has approved this contract for at least the stake.
+pub fn has_allowance(game: &GameStore, player: &Address) -> bool {
+    let Some(config) = game.get::<WagerConfig>(SessionKey::Wager) else {
+        return false;
+    };
+    allowance(game, &config, player) >= config.amount
+}
+
+/// Shared deposit flow: validate, run `transfer`, and check the escrow
+/// balance grew by exactly the stake.
+fn escrow<F>(game: &GameStore, player: &Address, transfer: F) -> Result<(), Error>
+where
+    F: FnOnce(&TokenClient, &Address, &i128) -> bool,
+{
+    let env = game.env();
+    let config: WagerConfig = game.get(SessionKey::Wager).ok_or(Error::InvalidWager)?;
+    if has_deposited(game, player) {
+        return Err(Error::AlreadyDeposited);
+    }
+
+    let token = TokenClient::new(env, &config.token);
+    let contract = env.current_contract_address();
+    let before = balance(&token, &contract);
+    if !transfer(&token, &contract, &config.amount) {
+        return Err(Error::TransferFailed);
+    }
+
+    // Reject tokens that deliver less than the transferred amount
+    if balance(&token, &contract) - before != config.amount {
+        return Err(Error::DepositShortfall);
+    }
+
+    game.set(SessionKey::Deposited(player.clone()), &true);
+    game.set(SessionKey::Escrowed, &(escrowed_by(game) + config.amount));
+    env.events().publish(
+        (symbol_short!("deposit"),),
+        (player.clone(), config.amount),
+    );
+    Ok(())
+}
+
+/// Pay the whole pot to `winner`.
+pub fn settle_winner(game: &GameStore, winner: &Address, p1: &Address, p2: &Address) {
+    let Some(config) = game.get::<WagerConfig>(SessionKey::Wager) else {
+        return;
+    };
+    let pot = escrowed(game, &config, p1, p2);
+    pay(game, &config, winner, pot);
+}
+
+/// Give each player back their own deposit.
+pub fn settle_draw(game: &GameStore, p1: &Address, p2: &Address) {
+    let Some(config) = game.get::<WagerConfig>(SessionKey::Wager) else {
+        return;
+    };
+    for player in [p1, p2] {
+        if has_deposited(game, player) {
+            pay(game, &config, player, config.amount);
+        }
+    }
+}
+
+/// Withdraw a payout that was held because the token refused it at settlement.
+pub fn withdraw(game: &GameStore, player: &Address) -> Result<i128, Error> {
+    let env = game.env();
+    let config: WagerConfig = game.get(SessionKey::Wager).ok_or(Error::InvalidWager)?;
+    let held: i128 = game.get(SessionKey::HeldPayout(player.clone())).unwrap_or(0);
+    if held <= 0 {
+        return Err(Error::NothingToWithdraw);
+    }
+
+    let token = TokenClient::new(env, &config.token);
+    match token.try_transfer(&env.current_contract_address(), &identity::account(game, player), &held) {
+        Ok(Ok(())) => {}
+        _ => return Err(Error::TransferFailed),
+    }
+    game.remove(SessionKey::HeldPayout(player.clone()));
+
+    env.events().publish(
+        (symbol_short!("withdraw"),),
+        (player.clone(), held),
+    );
+    Ok(held)
+}
+
+/// Amount held for `player` after a refused payout.
+pub fn held_payout(game: &GameStore, player: &Address) -> i128 {
+    game.get(SessionKey::HeldPayout(player.clone())).unwrap_or(0)
+}
+
+/// Total stake deposited by both players.
+fn escrowed(game: &GameStore, config: &WagerConfig, p1: &Address, p2: &Address) -> i128 {
+    let deposits = has_deposited(game, p1) as i128 + has_deposited(game, p2) as i128;
+    config.amount * deposits
+}
+
+/// Stake this game still holds in escrow. The contract's balance pools
+/// every game's stakes, so each game pays out of its own share only.
+fn escrowed_by(game: &GameStore) -> i128 {
+    game.get(SessionKey::Escrowed).unwrap_or(0)
+}
+
+/// Transfer `amount` out of this game's escrow, holding it for later
+/// withdrawal if the token refuses. A clawback may have shrunk the
+/// contract's balance, so never pay out more than is actually held, nor
+/// more than this game put in.
+fn pay(game: &GameStore, config: &WagerConfig, to: &Address, amount: i128) {
+    let env = game.env();
+    let token = TokenClient::new(env, &config.token);
+    let contract = env.current_contract_address();
+    let escrowed = escrowed_by(game);
+    let amount = amount.min(escrowed).min(balance(&token, &contract));
+    if amount <= 0 {
+        return;
+    }
+    game.set(SessionKey::Escrowed, &(escrowed - amount));
+
+    match token.try_transfer(&contract, &identity::account(game, to), &amount) {
+        Ok(Ok(())) => {
+            env.events().publish(
+                (symbol_short!("payout"),),
+                (to.clone(), amount),
+            );
+        }
+        _ => {
+            let held = held_payout(game, to) + amount;
+            game.set(SessionKey::HeldPayout(to.clone()), &held);
+            log!(env, "Payout refused by token; held for withdrawal");
+            env.events().publish(
+                (symbol_short!("pay_held"),),
+                (to.clone(), amount),
+            );
+        }
+    }
+}
+
+fn allowance(game: &GameStore, config: &WagerConfig, owner: &Address) -> i128 {
+    let token = TokenClient::new(game.env(), &config.token);
+    match token.try_allowance(&identity::account(game, owner), &game.env().current_contract_address()) {
+        Ok(Ok(allowance)) => allowance,
+        _ => 0,
+    }
+}
+
+fn balance(token: &TokenClient, owner: &Address) -> i128 {
+    match token.try_balance(owner) {
+        Ok(Ok(balance)) => balance,
+        _ => 0,
+    }
+}
@@ -0,0 +1,226 @@
+// ============================================================================
+// BN254 Groups
+// ============================================================================
+// G1 is y^2 = x^3 + 3 over Fp; G2 is its sextic twist y^2 = x^3 + 3/(9 + u)
+// over Fp2. Both share the short-Weierstrass arithmetic below: affine points
+// for encoding and the Miller loop, Jacobian coordinates for scalar products.
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+use super::field::{bit, Fp, Fp2};
+
+/// Field a BN254 group is defined over.
+pub trait CurveField:
+    Copy + Eq + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Neg<Output = Self>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn is_zero(&self) -> bool;
+    fn invert(&self) -> Self;
+    /// Constant term of the curve equation over this field.
+    fn curve_b() -> Self;
+
+    fn square(&self) -> Self {
+        *self * *self
+    }
+
+    fn double(&self) -> Self {
+        *self + *self
+    }
+}
+
+impl CurveField for Fp {
+    fn zero() -> Self {
+        Fp::zero()
+    }
+    fn one() -> Self {
+        Fp::one()
+    }
+    fn is_zero(&self) -> bool {
+        Fp::is_zero(self)
+    }
+    fn invert(&self) -> Self {
+        Fp::invert(self)
+    }
+    fn curve_b() -> Self {
+        Fp::from_u64(3)
+    }
+}
+
+impl CurveField for Fp2 {
+    fn zero() -> Self {
+        Fp2::zero()
+    }
+    fn one() -> Self {
+        Fp2::one()
+    }
+    fn is_zero(&self) -> bool {
+        Fp2::is_zero(self)
+    }
+    fn invert(&self) -> Self {
+        Fp2::invert(self)
+    }
+    fn curve_b() -> Self {
+        // 3 / (9 + u)
+        Fp2::from_raw(
+            [0x3267e6dc24a138e5, 0xb5b4c5e559dbefa3, 0x81be18991be06ac3, 0x2b149d40ceb8aaae],
+            [0xe4a2bd0685c315d2, 0xa74fa084e52d1852, 0xcd2cafadeed8fdf4, 0x009713b03af0fed4],
+        )
+    }
+}
+
+/// Point in affine coordinates.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Affine<F> {
+    pub x: F,
+    pub y: F,
+    pub infinity: bool,
+}
+
+pub type G1Affine = Affine<Fp>;
+pub type G2Affine = Affine<Fp2>;
+
+impl<F: CurveField> Affine<F> {
+    pub fn new(x: F, y: F) -> Self {
+        Self { x, y, infinity: false }
+    }
+
+    pub fn identity() -> Self {
+        Self { x: F::zero(), y: F::zero(), infinity: true }
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        self.infinity || self.y.square() == self.x.square() * self.x + F::curve_b()
+    }
+
+    pub fn to_jacobian(&self) -> Jacobian<F> {
+        if self.infinity {
+            Jacobian::identity()
+        } else {
+            Jacobian { x: self.x, y: self.y, z: F::one() }
+        }
+    }
+
+    /// Scalar product by a little-endian integer.
+    pub fn mul(&self, scalar: &[u64]) -> Self {
+        self.to_jacobian().mul(scalar).to_affine()
+    }
+}
+
+impl<F: CurveField> Neg for Affine<F> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self { y: -self.y, ..self }
+    }
+}
+
+impl G1Affine {
+    pub fn generator() -> Self {
+        Self::new(Fp::from_u64(1), Fp::from_u64(2))
+    }
+}
+
+impl G2Affine {
+    pub fn generator() -> Self {
+        Self::new(
+            Fp2::from_raw(
+                [0x46debd5cd992f6ed, 0x674322d4f75edadd, 0x426a00665e5c4479, 0x1800deef121f1e76],
+                [0x97e485b7aef312c2, 0xf1aa493335a9e712, 0x7260bfb731fb5d25, 0x198e9393920d483a],
+            ),
+            Fp2::from_raw(
+                [0x4ce6cc0166fa7daa, 0xe3d1e7690c43d37b, 0x4aab71808dcb408f, 0x12c85ea5db8c6deb],
+                [0x55acdadcd122975b, 0xbc4b313370b38ef3, 0xec9e99ad690c3395, 0x090689d0585ff075],
+            ),
+        )
+    }
+
+    /// Whether the point lies in the order-r subgroup. G2 has a large
+    /// cofactor, so points on the twist are not necessarily in G2.
+    pub fn is_torsion_free(&self) -> bool {
+        self.to_jacobian().mul(&super::field::Fr::MODULUS).is_identity()
+    }
+}
+
+/// Point in Jacobian coordinates: (X / Z^2, Y / Z^3).
+#[derive(Copy, Clone, Debug)]
+pub struct Jacobian<F> {
+    pub(super) x: F,
+    pub(super) y: F,
+    pub(super) z: F,
+}
+
+impl<F: CurveField> Jacobian<F> {
+    pub fn identity() -> Self {
+        Self { x: F::one(), y: F::one(), z: F::zero() }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    pub fn to_affine(&self) -> Affine<F> {
+        if self.is_identity() {
+            return Affine::identity();
+        }
+        let z_inv = self.z.invert();
+        let z_inv2 = z_inv.square();
+        Affine::new(self.x * z_inv2, self.y * z_inv2 * z_inv)
+    }
+
+    /// dbl-2009-l
+    pub fn double(&self) -> Self {
+        if self.is_identity() {
+            return *self;
+        }
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = b.square();
+        let d = ((self.x + b).square() - a - c).double();
+        let e = a.double() + a;
+        let f = e.square();
+        let x = f - d.double();
+        let y = e * (d - x) - c.double().double().double();
+        let z = (self.y * self.z).double();
+        Self { x, y, z }
+    }
+
+    /// add-2007-bl
+    pub fn add(&self, other: &Self) -> Self {
+        if self.is_identity() {
+            return *other;
+        }
+        if other.is_identity() {
+            return *self;
+        }
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        let u1 = self.x * z2z2;
+        let u2 = other.x * z1z1;
+        let s1 = self.y * other.z * z2z2;
+        let s2 = other.y * self.z * z1z1;
+        let h = u2 - u1;
+        let r = (s2 - s1).double();
+        if h.is_zero() {
+            return if r.is_zero() { self.double() } else { Self::identity() };
+        }
+        let i = h.double().square();
+        let j = h * i;
+        let v = u1 * i;
+        let x = r.square() - j - v.double();
+        let y = r * (v - x) - (s1 * j).double();
+        let z = ((self.z + other.z).square() - z1z1 - z2z2) * h;
+        Self { x, y, z }
+    }
+
+    /// Scalar product by a little-endian integer.
+    pub fn mul(&self, scalar: &[u64]) -> Self {
+        let mut acc = Self::identity();
+        for i in (0..scalar.len() * 64).rev() {
+            acc = acc.double();
+            if bit(scalar, i) {
+                acc = acc.add(self);
+            }
+        }
+        acc
+    }
+}
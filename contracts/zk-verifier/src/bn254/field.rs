@@ -0,0 +1,459 @@
+// ============================================================================
+// BN254 Fields
+// ============================================================================
+// 256-bit prime fields in Montgomery form (four little-endian u64 limbs), the
+// quadratic extension Fp2 = Fp[u]/(u^2 + 1), and Fp12 = Fp[w]/(w^12 - 18w^6 + 82)
+// as a plain polynomial, where w^6 = 9 + u.
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+/// a + b * c + carry, returned as (low, high) words.
+#[inline(always)]
+const fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = (a as u128) + (b as u128) * (c as u128) + (carry as u128);
+    (t as u64, (t >> 64) as u64)
+}
+
+/// a + b + carry, returned as (sum, carry).
+#[inline(always)]
+const fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = (a as u128) + (b as u128) + (carry as u128);
+    (t as u64, (t >> 64) as u64)
+}
+
+/// a - b - borrow, returned as (difference, borrow).
+#[inline(always)]
+const fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub((b as u128) + (borrow as u128));
+    (t as u64, (t >> 127) as u64)
+}
+
+fn geq(a: &[u64; 4], b: &[u64; 4]) -> bool {
+    for i in (0..4).rev() {
+        if a[i] != b[i] {
+            return a[i] > b[i];
+        }
+    }
+    true
+}
+
+fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut out = [0u64; 4];
+    let mut borrow = 0;
+    for i in 0..4 {
+        (out[i], borrow) = sbb(a[i], b[i], borrow);
+    }
+    (out, borrow)
+}
+
+/// Big-endian bytes to little-endian limbs.
+pub(crate) fn limbs_from_be(bytes: &[u8; 32]) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let start = 32 - 8 * (i + 1);
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&bytes[start..start + 8]);
+        *limb = u64::from_be_bytes(word);
+    }
+    limbs
+}
+
+fn limbs_to_be(limbs: &[u64; 4]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (i, limb) in limbs.iter().enumerate() {
+        let start = 32 - 8 * (i + 1);
+        bytes[start..start + 8].copy_from_slice(&limb.to_be_bytes());
+    }
+    bytes
+}
+
+/// Bit `i` of a little-endian limb array.
+pub(crate) fn bit(limbs: &[u64], i: usize) -> bool {
+    (limbs[i / 64] >> (i % 64)) & 1 == 1
+}
+
+macro_rules! prime_field {
+    ($name:ident, modulus: $modulus:expr, r2: $r2:expr, inv: $inv:expr, one: $one:expr) => {
+        #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+        pub struct $name([u64; 4]);
+
+        impl $name {
+            pub const MODULUS: [u64; 4] = $modulus;
+            const R2: [u64; 4] = $r2;
+            const INV: u64 = $inv;
+
+            pub const fn zero() -> Self {
+                Self([0; 4])
+            }
+
+            pub const fn one() -> Self {
+                Self($one)
+            }
+
+            /// Element from canonical little-endian limbs; must be below the modulus.
+            pub fn from_raw(limbs: [u64; 4]) -> Self {
+                Self(limbs).mont_mul(&Self(Self::R2))
+            }
+
+            pub fn from_u64(value: u64) -> Self {
+                Self::from_raw([value, 0, 0, 0])
+            }
+
+            /// Parse a canonical big-endian encoding; `None` if not below the modulus.
+            pub fn from_be_bytes(bytes: &[u8; 32]) -> Option<Self> {
+                let limbs = limbs_from_be(bytes);
+                if geq(&limbs, &Self::MODULUS) {
+                    return None;
+                }
+                Some(Self::from_raw(limbs))
+            }
+
+            /// Big-endian 256-bit integer reduced modulo the field, e.g. a
+            /// SHA-256 digest entering a circuit.
+            pub fn from_be_bytes_reduced(bytes: &[u8; 32]) -> Self {
+                let mut limbs = limbs_from_be(bytes);
+                while geq(&limbs, &Self::MODULUS) {
+                    limbs = sub_limbs(&limbs, &Self::MODULUS).0;
+                }
+                Self::from_raw(limbs)
+            }
+
+            pub fn to_be_bytes(&self) -> [u8; 32] {
+                limbs_to_be(&self.to_raw())
+            }
+
+            /// Canonical little-endian limbs.
+            pub fn to_raw(&self) -> [u64; 4] {
+                self.mont_mul(&Self([1, 0, 0, 0])).0
+            }
+
+            pub fn is_zero(&self) -> bool {
+                self.0 == [0; 4]
+            }
+
+            pub fn square(&self) -> Self {
+                self.mont_mul(self)
+            }
+
+            pub fn double(&self) -> Self {
+                *self + *self
+            }
+
+            /// `self^exp` for a little-endian exponent.
+            pub fn pow(&self, exp: &[u64]) -> Self {
+                let mut acc = Self::one();
+                for i in (0..exp.len() * 64).rev() {
+                    acc = acc.square();
+                    if bit(exp, i) {
+                        acc = acc * *self;
+                    }
+                }
+                acc
+            }
+
+            /// Multiplicative inverse by Fermat; zero maps to zero.
+            pub fn invert(&self) -> Self {
+                let (exp, _) = sub_limbs(&Self::MODULUS, &[2, 0, 0, 0]);
+                self.pow(&exp)
+            }
+
+            /// Montgomery multiplication (CIOS).
+            fn mont_mul(&self, other: &Self) -> Self {
+                let (a, b, m) = (&self.0, &other.0, &Self::MODULUS);
+                let mut t = [0u64; 6];
+                for i in 0..4 {
+                    let mut carry = 0;
+                    for j in 0..4 {
+                        (t[j], carry) = mac(t[j], a[j], b[i], carry);
+                    }
+                    (t[4], t[5]) = adc(t[4], carry, 0);
+
+                    let k = t[0].wrapping_mul(Self::INV);
+                    let (_, mut carry) = mac(t[0], k, m[0], 0);
+                    for j in 1..4 {
+                        (t[j - 1], carry) = mac(t[j], k, m[j], carry);
+                    }
+                    (t[3], carry) = adc(t[4], carry, 0);
+                    t[4] = t[5] + carry;
+                }
+                let out = [t[0], t[1], t[2], t[3]];
+                if t[4] != 0 || geq(&out, m) {
+                    Self(sub_limbs(&out, m).0)
+                } else {
+                    Self(out)
+                }
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                let mut out = [0u64; 4];
+                let mut carry = 0;
+                for i in 0..4 {
+                    (out[i], carry) = adc(self.0[i], rhs.0[i], carry);
+                }
+                // Both moduli are below 2^254, so the sum cannot overflow
+                if geq(&out, &Self::MODULUS) {
+                    out = sub_limbs(&out, &Self::MODULUS).0;
+                }
+                Self(out)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                let (mut out, borrow) = sub_limbs(&self.0, &rhs.0);
+                if borrow != 0 {
+                    let mut carry = 0;
+                    for i in 0..4 {
+                        (out[i], carry) = adc(out[i], Self::MODULUS[i], carry);
+                    }
+                }
+                Self(out)
+            }
+        }
+
+        impl Mul for $name {
+            type Output = Self;
+            fn mul(self, rhs: Self) -> Self {
+                self.mont_mul(&rhs)
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+            fn neg(self) -> Self {
+                Self::zero() - self
+            }
+        }
+    };
+}
+
+prime_field!(
+    Fp,
+    modulus: [0x3c208c16d87cfd47, 0x97816a916871ca8d, 0xb85045b68181585d, 0x30644e72e131a029],
+    r2: [0xf32cfc5b538afa89, 0xb5e71911d44501fb, 0x47ab1eff0a417ff6, 0x06d89f71cab8351f],
+    inv: 0x87d20782e4866389,
+    one: [0xd35d438dc58f0d9d, 0x0a78eb28f5c70b3d, 0x666ea36f7879462c, 0x0e0a77c19a07df2f]
+);
+
+prime_field!(
+    Fr,
+    modulus: [0x43e1f593f0000001, 0x2833e84879b97091, 0xb85045b68181585d, 0x30644e72e131a029],
+    r2: [0x1bb8e645ae216da7, 0x53fe3ab1e35c59e3, 0x8c49833d53bb8085, 0x0216d0b17f4e44a5],
+    inv: 0xc2e1f593efffffff,
+    one: [0xac96341c4ffffffb, 0x36fc76959f60cd29, 0x666ea36f7879462e, 0x0e0a77c19a07df2f]
+);
+
+/// Element `c0 + c1 * u` of Fp2, with u^2 = -1.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Fp2 {
+    pub c0: Fp,
+    pub c1: Fp,
+}
+
+impl Fp2 {
+    pub const fn new(c0: Fp, c1: Fp) -> Self {
+        Self { c0, c1 }
+    }
+
+    pub const fn zero() -> Self {
+        Self::new(Fp::zero(), Fp::zero())
+    }
+
+    pub const fn one() -> Self {
+        Self::new(Fp::one(), Fp::zero())
+    }
+
+    pub fn from_raw(c0: [u64; 4], c1: [u64; 4]) -> Self {
+        Self::new(Fp::from_raw(c0), Fp::from_raw(c1))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    pub fn square(&self) -> Self {
+        *self * *self
+    }
+
+    pub fn double(&self) -> Self {
+        *self + *self
+    }
+
+    /// Frobenius map, which on Fp2 is conjugation.
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.c0, -self.c1)
+    }
+
+    pub fn scale(&self, k: Fp) -> Self {
+        Self::new(self.c0 * k, self.c1 * k)
+    }
+
+    pub fn invert(&self) -> Self {
+        let norm = (self.c0.square() + self.c1.square()).invert();
+        Self::new(self.c0 * norm, -(self.c1 * norm))
+    }
+}
+
+impl Add for Fp2 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.c0 + rhs.c0, self.c1 + rhs.c1)
+    }
+}
+
+impl Sub for Fp2 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.c0 - rhs.c0, self.c1 - rhs.c1)
+    }
+}
+
+impl Mul for Fp2 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.c0 * rhs.c0 - self.c1 * rhs.c1,
+            self.c0 * rhs.c1 + self.c1 * rhs.c0,
+        )
+    }
+}
+
+impl Neg for Fp2 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.c0, -self.c1)
+    }
+}
+
+/// Element of Fp12 as a polynomial of degree < 12 in w.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Fp12(pub [Fp; 12]);
+
+impl Fp12 {
+    pub fn one() -> Self {
+        let mut coeffs = [Fp::zero(); 12];
+        coeffs[0] = Fp::one();
+        Self(coeffs)
+    }
+
+    pub fn is_one(&self) -> bool {
+        *self == Self::one()
+    }
+
+    /// Add `c * w^k` for `c` in Fp2. Fp2 embeds through u = w^6 - 9.
+    pub fn add_fp2_term(&mut self, c: Fp2, k: usize) {
+        let nine = Fp::from_u64(9);
+        self.0[k] = self.0[k] + c.c0 - nine * c.c1;
+        self.0[k + 6] = self.0[k + 6] + c.c1;
+    }
+
+    /// Coefficient of `w^k` for `k < 6`, reading Fp12 as Fp2[w]/(w^6 - (9 + u)).
+    fn fp2_coeff(&self, k: usize) -> Fp2 {
+        Fp2::new(self.0[k] + Fp::from_u64(9) * self.0[k + 6], self.0[k + 6])
+    }
+
+    pub fn square(&self) -> Self {
+        // Each cross product once, doubled
+        let mut wide = [Fp::zero(); 23];
+        for i in 0..12 {
+            wide[2 * i] = wide[2 * i] + self.0[i].square();
+            for j in i + 1..12 {
+                wide[i + j] = wide[i + j] + (self.0[i] * self.0[j]).double();
+            }
+        }
+        Self::reduce(&wide)
+    }
+
+    /// `self^(p^6)`, which sends w to -w. On the unitary elements left by
+    /// the first step of the final exponentiation, this is the inverse.
+    pub fn conjugate(&self) -> Self {
+        let mut out = *self;
+        for c in out.0.iter_mut().skip(1).step_by(2) {
+            *c = -*c;
+        }
+        out
+    }
+
+    /// The p-power Frobenius map: w^p = (9 + u)^((p - 1) / 6) w.
+    pub fn frobenius(&self) -> Self {
+        let gamma = Fp2::from_raw(
+            [0xd60b35dadcc9e470, 0x5c521e08292f2176, 0xe8b99fdd76e68b60, 0x1284b71c2865a7df],
+            [0xca5cf05f80f362ac, 0x747992778eeec7e5, 0xa6327cfe12150b8e, 0x246996f3b4fae7e6],
+        );
+        let mut out = Self([Fp::zero(); 12]);
+        let mut gamma_k = Fp2::one();
+        for k in 0..6 {
+            out.add_fp2_term(self.fp2_coeff(k).conjugate() * gamma_k, k);
+            gamma_k = gamma_k * gamma;
+        }
+        out
+    }
+
+    /// Multiplicative inverse; zero maps to zero.
+    pub fn invert(&self) -> Self {
+        // self * conjugate lies in Fp6 = Fp2[v]/(v^3 - (9 + u)) with v = w^2,
+        // where the inverse needs a single inversion in Fp
+        let norm = *self * self.conjugate();
+        let (a, b, c) = (norm.fp2_coeff(0), norm.fp2_coeff(2), norm.fp2_coeff(4));
+        let xi = Fp2::new(Fp::from_u64(9), Fp::one());
+        let t0 = a.square() - xi * b * c;
+        let t1 = xi * c.square() - a * b;
+        let t2 = b.square() - a * c;
+        let det = (a * t0 + xi * (c * t1 + b * t2)).invert();
+
+        let mut inv = Self([Fp::zero(); 12]);
+        inv.add_fp2_term(t0 * det, 0);
+        inv.add_fp2_term(t1 * det, 2);
+        inv.add_fp2_term(t2 * det, 4);
+        self.conjugate() * inv
+    }
+
+    /// `self^exp` for a little-endian exponent.
+    pub fn pow(&self, exp: &[u64]) -> Self {
+        let mut acc = Self::one();
+        for i in (0..exp.len() * 64).rev() {
+            acc = acc.square();
+            if bit(exp, i) {
+                acc = acc * *self;
+            }
+        }
+        acc
+    }
+
+    /// Reduce a product of degree < 23 with w^12 = 18w^6 - 82.
+    fn reduce(wide: &[Fp; 23]) -> Self {
+        let mut wide = *wide;
+        let (c18, c82) = (Fp::from_u64(18), Fp::from_u64(82));
+        for k in (12..23).rev() {
+            let top = wide[k];
+            wide[k - 6] = wide[k - 6] + c18 * top;
+            wide[k - 12] = wide[k - 12] - c82 * top;
+        }
+        let mut out = [Fp::zero(); 12];
+        out.copy_from_slice(&wide[..12]);
+        Self(out)
+    }
+}
+
+impl Mul for Fp12 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        // Schoolbook product, skipping the zero coefficients of sparse lines
+        let mut wide = [Fp::zero(); 23];
+        for (i, a) in self.0.iter().enumerate() {
+            if a.is_zero() {
+                continue;
+            }
+            for (j, b) in rhs.0.iter().enumerate() {
+                if !b.is_zero() {
+                    wide[i + j] = wide[i + j] + *a * *b;
+                }
+            }
+        }
+        Self::reduce(&wide)
+    }
+}
@@ -0,0 +1,116 @@
+// ============================================================================
+// BN254 (alt_bn128)
+// ============================================================================
+// The pairing-friendly curve Noir, Circom and gnark target for Groth16, in
+// pure Rust. This SDK exposes pairings only over BLS12-381, so proofs over
+// BN254 are checked with the arithmetic here until the host offers BN254.
+//
+// Points use the Ethereum precompile encoding (EIP-196/197): big-endian
+// coordinates, G2 coordinates as (imaginary, real), all zeros for infinity.
+
+mod curve;
+mod field;
+mod pairing;
+
+pub use curve::{Affine, CurveField, G1Affine, G2Affine, Jacobian};
+pub use field::{Fp, Fp12, Fp2, Fr};
+pub use pairing::{pairing, pairing_check};
+
+fn fp_at(bytes: &[u8], offset: usize) -> Option<Fp> {
+    let mut word = [0u8; 32];
+    word.copy_from_slice(&bytes[offset..offset + 32]);
+    Fp::from_be_bytes(&word)
+}
+
+/// Decode a G1 point, rejecting non-canonical coordinates and points off
+/// the curve. G1 has cofactor one, so no subgroup check is needed.
+pub fn g1_from_bytes(bytes: &[u8; 64]) -> Option<G1Affine> {
+    if bytes.iter().all(|b| *b == 0) {
+        return Some(G1Affine::identity());
+    }
+    let point = G1Affine::new(fp_at(bytes, 0)?, fp_at(bytes, 32)?);
+    point.is_on_curve().then_some(point)
+}
+
+/// Decode a G2 point, rejecting non-canonical coordinates, points off the
+/// twist, and points outside the order-r subgroup.
+pub fn g2_from_bytes(bytes: &[u8; 128]) -> Option<G2Affine> {
+    g2_from_bytes_unchecked(bytes).filter(G2Affine::is_torsion_free)
+}
+
+/// Decode a G2 point already known to lie in G2, such as one that
+/// `g2_from_bytes` accepted when it was stored. Coordinates are still
+/// checked to be canonical and on the twist; the subgroup check, the
+/// costly part, is skipped.
+pub fn g2_from_bytes_unchecked(bytes: &[u8; 128]) -> Option<G2Affine> {
+    if bytes.iter().all(|b| *b == 0) {
+        return Some(G2Affine::identity());
+    }
+    let x = Fp2::new(fp_at(bytes, 32)?, fp_at(bytes, 0)?);
+    let y = Fp2::new(fp_at(bytes, 96)?, fp_at(bytes, 64)?);
+    let point = G2Affine::new(x, y);
+    point.is_on_curve().then_some(point)
+}
+
+pub fn g1_to_bytes(point: &G1Affine) -> [u8; 64] {
+    let mut bytes = [0u8; 64];
+    if !point.infinity {
+        bytes[..32].copy_from_slice(&point.x.to_be_bytes());
+        bytes[32..].copy_from_slice(&point.y.to_be_bytes());
+    }
+    bytes
+}
+
+pub fn g2_to_bytes(point: &G2Affine) -> [u8; 128] {
+    let mut bytes = [0u8; 128];
+    if !point.infinity {
+        for (i, c) in [point.x.c1, point.x.c0, point.y.c1, point.y.c0].iter().enumerate() {
+            bytes[32 * i..32 * (i + 1)].copy_from_slice(&c.to_be_bytes());
+        }
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pairing_is_bilinear() {
+        let (g1, g2) = (G1Affine::generator(), G2Affine::generator());
+        assert!(g2.is_on_curve() && g2.is_torsion_free());
+
+        let e = pairing(&g1, &g2);
+        assert!(!e.is_one());
+        assert_eq!(pairing(&g1.mul(&[6]), &g2), pairing(&g1.mul(&[2]), &g2.mul(&[3])));
+        assert_eq!(pairing(&g1, &g2.mul(&[5])), e.pow(&[5]));
+        assert!(e.pow(&Fr::MODULUS).is_one());
+        assert!(pairing_check(&[(g1.mul(&[7]), g2), (-g1, g2.mul(&[7]))]));
+        assert!(!pairing_check(&[(g1.mul(&[7]), g2), (-g1, g2.mul(&[8]))]));
+
+        let round_trip = g2_from_bytes(&g2_to_bytes(&g2.mul(&[9]))).unwrap();
+        assert_eq!(round_trip, g2.mul(&[9]));
+    }
+
+    #[test]
+    fn test_fp12_maps() {
+        let mut f = Fp12::one();
+        for (i, c) in f.0.iter_mut().enumerate() {
+            *c = Fp::from_u64(3 + 7 * i as u64);
+        }
+        assert!((f * f.invert()).is_one());
+        assert_eq!(f.square(), f * f);
+
+        // Frobenius is the p-th power: twelve of them are the identity, six
+        // the conjugation
+        let mut g = f;
+        for i in 1..=12 {
+            g = g.frobenius();
+            assert_eq!(g == f, i == 12);
+            if i == 6 {
+                assert_eq!(g, f.conjugate());
+            }
+        }
+        assert_eq!(f.frobenius(), f.pow(&Fp::MODULUS));
+    }
+}
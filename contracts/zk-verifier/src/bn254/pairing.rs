@@ -0,0 +1,166 @@
+// ============================================================================
+// Optimal Ate Pairing
+// ============================================================================
+// Miller loop over 6x + 2 with the G2 point kept on the twist in Jacobian
+// coordinates, lines evaluated at the G1 point and embedded into Fp12
+// through (x, y) -> (x w^2, y w^3). Lines are scaled by a denominator in
+// Fp2 rather than inverted, and vertical lines dropped: both lie in Fp6,
+// which the final exponentiation sends to one.
+
+use super::curve::{G1Affine, G2Affine, Jacobian};
+use super::field::{Fp, Fp12, Fp2};
+
+/// 6x + 2 for the BN parameter x = 4965661367192848881.
+const ATE_LOOP_COUNT: u128 = 29793968203157093288;
+
+/// The BN parameter x.
+const BN_X: u64 = 4965661367192848881;
+
+/// Whether the product of the pairings of `pairs` is one. This is the
+/// check behind pairing-based verifiers: one shared Miller loop, one final
+/// exponentiation.
+pub fn pairing_check(pairs: &[(G1Affine, G2Affine)]) -> bool {
+    final_exponentiation(&miller_loop(pairs)).is_one()
+}
+
+/// The reduced pairing e(P, Q), up to the fixed power of `final_exponentiation`.
+pub fn pairing(p: &G1Affine, q: &G2Affine) -> Fp12 {
+    final_exponentiation(&miller_loop(&[(*p, *q)]))
+}
+
+/// f^((p^12 - 1) / r), up to a fixed power coprime to r: the easy part
+/// (p^6 - 1)(p^2 + 1) with a Frobenius and one inversion, then the hard
+/// part (p^4 - p^2 + 1) / r as three exponentiations by x (Fuentes-Castaneda
+/// et al., "Faster hashing to G2"), in the chain of Granger and Scott.
+fn final_exponentiation(f: &Fp12) -> Fp12 {
+    let r = f.conjugate() * f.invert();
+    let r = r.frobenius().frobenius() * r;
+
+    // r is now unitary, so conjugation inverts it
+    let exp_by_neg_x = |f: &Fp12| f.pow(&[BN_X]).conjugate();
+    let y0 = exp_by_neg_x(&r);
+    let y1 = y0.square();
+    let y2 = y1.square();
+    let y3 = y2 * y1;
+    let y4 = exp_by_neg_x(&y3);
+    let y5 = y4.square();
+    let y6 = exp_by_neg_x(&y5);
+    let y7 = y6.conjugate() * y4;
+    let y8 = y7 * y3.conjugate();
+    let y9 = y8 * y1;
+    let y10 = y8 * y4;
+    let y11 = y10 * r;
+    let y13 = y9.frobenius() * y11;
+    let y14 = y8.frobenius().frobenius() * y13;
+    let y15 = r.conjugate() * y9;
+    y15.frobenius().frobenius().frobenius() * y14
+}
+
+/// Product of the Miller functions of `pairs`, sharing the squarings.
+/// Pairs with a point at infinity contribute one. At most four pairs.
+fn miller_loop(pairs: &[(G1Affine, G2Affine)]) -> Fp12 {
+    let mut live = [(G1Affine::identity(), G2Affine::identity()); 4];
+    let mut n = 0;
+    for (p, q) in pairs.iter().filter(|(p, q)| !p.infinity && !q.infinity) {
+        live[n] = (*p, *q);
+        n += 1;
+    }
+    let live = &live[..n];
+
+    let mut t = [Jacobian::identity(); 4];
+    for (i, (_, q)) in live.iter().enumerate() {
+        t[i] = q.to_jacobian();
+    }
+
+    let mut f = Fp12::one();
+    // The top bit is accounted for by starting at T = Q
+    for i in (0..64).rev() {
+        f = f.square();
+        for (k, (p, _)) in live.iter().enumerate() {
+            f = f * double_step(&mut t[k], p);
+        }
+        if (ATE_LOOP_COUNT >> i) & 1 == 1 {
+            for (k, (p, q)) in live.iter().enumerate() {
+                f = f * add_step(&mut t[k], q, p);
+            }
+        }
+    }
+
+    // Correction steps with pi(Q) and -pi^2(Q)
+    for (k, (p, q)) in live.iter().enumerate() {
+        let q1 = frobenius(q);
+        let q2 = -frobenius(&q1);
+        f = f * add_step(&mut t[k], &q1, p);
+        f = f * add_step(&mut t[k], &q2, p);
+    }
+    f
+}
+
+/// The line l(P) = -yP + (lambda xP) w + (yT - lambda xT) w^3, scaled by
+/// `scale` in Fp2 to clear the denominator of lambda = `num` / `scale`.
+/// Such factors lie in Fp6, which the final exponentiation sends to one.
+fn line(p: &G1Affine, num: Fp2, scale: Fp2, c3: Fp2) -> Fp12 {
+    let mut line = Fp12([Fp::zero(); 12]);
+    line.add_fp2_term(-scale.scale(p.y), 0);
+    line.add_fp2_term(num.scale(p.x), 1);
+    line.add_fp2_term(c3, 3);
+    line
+}
+
+/// Evaluate at `p` the tangent at `t`, and double `t`.
+fn double_step(t: &mut Jacobian<Fp2>, p: &G1Affine) -> Fp12 {
+    if t.is_identity() {
+        return Fp12::one();
+    }
+    // lambda = 3x^2 / 2y = 3X^2 Z^2 / 2Y Z^3
+    let (xx, zz) = (t.x.square(), t.z.square());
+    let xx3 = xx.double() + xx;
+    let scale = (t.y * zz * t.z).double();
+    // (yT - lambda xT) * 2Y Z^3 = 2Y^2 - 3X^3
+    let c3 = t.y.square().double() - xx3 * t.x;
+    let line = line(p, xx3 * zz, scale, c3);
+    *t = t.double();
+    line
+}
+
+/// Evaluate at `p` the line through `t` and `q` (the tangent if equal),
+/// and advance `t` to `t + q`.
+fn add_step(t: &mut Jacobian<Fp2>, q: &G2Affine, p: &G1Affine) -> Fp12 {
+    if t.is_identity() {
+        // Vertical line through q
+        *t = q.to_jacobian();
+        return Fp12::one();
+    }
+    // lambda = (yQ - yT) / (xQ - xT) = (yQ Z^3 - Y) / Z (xQ Z^2 - X)
+    let zz = t.z.square();
+    let zzz = zz * t.z;
+    let h = q.x * zz - t.x;
+    let num = q.y * zzz - t.y;
+    if h.is_zero() {
+        if num.is_zero() {
+            return double_step(t, p);
+        }
+        // Vertical line: t + q is the point at infinity
+        *t = Jacobian::identity();
+        return Fp12::one();
+    }
+    let scale = t.z * h;
+    let line = line(p, num, scale, q.y * scale - num * q.x);
+    *t = t.add(&q.to_jacobian());
+    line
+}
+
+/// The p-power Frobenius endomorphism, acting on the twist.
+fn frobenius(q: &G2Affine) -> G2Affine {
+    // (9 + u)^((p - 1) / 3)
+    let gamma_x = Fp2::from_raw(
+        [0x99e39557176f553d, 0xb78cc310c2c3330c, 0x4c0bec3cf559b143, 0x2fb347984f7911f7],
+        [0x1665d51c640fcba2, 0x32ae2a1d0b7c9dce, 0x4ba4cc8bd75a0794, 0x16c9e55061ebae20],
+    );
+    // (9 + u)^((p - 1) / 2)
+    let gamma_y = Fp2::from_raw(
+        [0xdc54014671a0135a, 0xdbaae0eda9c95998, 0xdc5ec698b6e2f9b9, 0x063cf305489af5dc],
+        [0x82d37f632623b0e3, 0x21807dc98fa25bd2, 0x0704b5a7ec796f2b, 0x07c03cbcac41049a],
+    );
+    G2Affine::new(q.x.conjugate() * gamma_x, q.y.conjugate() * gamma_y)
+}
@@ -0,0 +1,190 @@
+// ============================================================================
+// Groth16 Verification (BN254)
+// ============================================================================
+// Checks a Groth16 proof against a circuit's verification key:
+//
+//   e(A, B) = e(alpha, beta) * e(L, gamma) * e(C, delta),
+//   L = IC[0] + sum(input[i] * IC[i + 1])
+//
+// evaluated as one four-pair pairing check. Proofs are 256 bytes,
+// A (G1) || B (G2) || C (G1), in the encoding of `bn254` — the layout
+// snarkjs and gnark export for Ethereum verifiers.
+
+use crate::bn254::{self, Fr, G1Affine, G2Affine, Jacobian};
+
+/// Groth16 verification key of a circuit, as encoded points. `ic` yields
+/// one point per public input, plus the constant term first; it is any
+/// iterator so callers need not collect the points into a slice.
+pub struct VerificationKey<I> {
+    pub alpha: [u8; 64],
+    pub beta: [u8; 128],
+    pub gamma: [u8; 128],
+    pub delta: [u8; 128],
+    pub ic: I,
+}
+
+/// Whether every point of `vk` decodes and it has at least the constant term.
+pub fn is_well_formed<I: IntoIterator<Item = [u8; 64]>>(vk: VerificationKey<I>) -> bool {
+    let mut ic = vk.ic.into_iter().peekable();
+    ic.peek().is_some()
+        && bn254::g1_from_bytes(&vk.alpha).is_some()
+        && bn254::g2_from_bytes(&vk.beta).is_some()
+        && bn254::g2_from_bytes(&vk.gamma).is_some()
+        && bn254::g2_from_bytes(&vk.delta).is_some()
+        && ic.all(|point| bn254::g1_from_bytes(&point).is_some())
+}
+
+/// Whether `proof` is valid for `public_inputs` under `vk`. Malformed
+/// points, in the proof or the key, and a wrong input count all fail.
+pub fn verify<I: IntoIterator<Item = [u8; 64]>>(vk: VerificationKey<I>, proof: &[u8; 256], public_inputs: &[Fr]) -> bool {
+    verify_with(vk, proof, public_inputs, bn254::g2_from_bytes)
+}
+
+/// As `verify`, for a key that already passed `is_well_formed`, e.g. when
+/// it was registered: its G2 points are not checked again for the
+/// subgroup, which costs more than the rest of decoding. The proof's points
+/// are checked in full.
+pub fn verify_well_formed<I: IntoIterator<Item = [u8; 64]>>(
+    vk: VerificationKey<I>,
+    proof: &[u8; 256],
+    public_inputs: &[Fr],
+) -> bool {
+    verify_with(vk, proof, public_inputs, bn254::g2_from_bytes_unchecked)
+}
+
+fn verify_with<I: IntoIterator<Item = [u8; 64]>>(
+    vk: VerificationKey<I>,
+    proof: &[u8; 256],
+    public_inputs: &[Fr],
+    key_g2: fn(&[u8; 128]) -> Option<G2Affine>,
+) -> bool {
+    let (Some(a), Some(b), Some(c)) = (g1_at(proof, 0), g2_at(proof, 64), g1_at(proof, 192)) else {
+        return false;
+    };
+    let (Some(alpha), Some(beta), Some(gamma), Some(delta)) =
+        (bn254::g1_from_bytes(&vk.alpha), key_g2(&vk.beta), key_g2(&vk.gamma), key_g2(&vk.delta))
+    else {
+        return false;
+    };
+
+    let mut ic = vk.ic.into_iter().map(|point| bn254::g1_from_bytes(&point));
+    let Some(Some(ic0)) = ic.next() else {
+        return false;
+    };
+    let mut l: Jacobian<_> = ic0.to_jacobian();
+    let mut inputs = public_inputs.iter();
+    for point in ic {
+        let (Some(point), Some(input)) = (point, inputs.next()) else {
+            return false;
+        };
+        l = l.add(&point.to_jacobian().mul(&input.to_raw()));
+    }
+    if inputs.next().is_some() {
+        return false;
+    }
+
+    bn254::pairing_check(&[(-a, b), (alpha, beta), (l.to_affine(), gamma), (c, delta)])
+}
+
+fn g1_at(bytes: &[u8; 256], offset: usize) -> Option<G1Affine> {
+    bn254::g1_from_bytes(bytes[offset..offset + 64].try_into().unwrap())
+}
+
+fn g2_at(bytes: &[u8; 256], offset: usize) -> Option<G2Affine> {
+    bn254::g2_from_bytes(bytes[offset..offset + 128].try_into().unwrap())
+}
+
+#[cfg(any(test, feature = "testutils"))]
+pub mod testutils {
+    use super::VerificationKey;
+    use crate::bn254::{self, Fr, G1Affine, G2Affine};
+
+    /// A Groth16 setup whose toxic waste is kept, so it can produce a valid
+    /// proof for any public inputs without a circuit. Tests only: such a
+    /// key proves nothing.
+    pub struct Trapdoor {
+        alpha: Fr,
+        beta: Fr,
+        gamma: Fr,
+        delta: Fr,
+        inputs: u32,
+    }
+
+    impl Trapdoor {
+        pub fn new(inputs: u32) -> Self {
+            Self {
+                alpha: Fr::from_u64(0x5eed_a1fa),
+                beta: Fr::from_u64(0x5eed_be7a),
+                gamma: Fr::from_u64(0x5eed_9a33),
+                delta: Fr::from_u64(0x5eed_de17),
+                inputs,
+            }
+        }
+
+        fn ic(&self, i: u32) -> Fr {
+            Fr::from_u64(0x1c00 + i as u64)
+        }
+
+        pub fn verification_key(&self) -> VerificationKey<impl Iterator<Item = [u8; 64]> + '_> {
+            VerificationKey {
+                alpha: g1(&self.alpha),
+                beta: g2(&self.beta),
+                gamma: g2(&self.gamma),
+                delta: g2(&self.delta),
+                ic: (0..=self.inputs).map(|i| g1(&self.ic(i))),
+            }
+        }
+
+        /// A proof that verifies for `public_inputs` under this key.
+        pub fn prove(&self, public_inputs: &[Fr]) -> [u8; 256] {
+            let (a, b) = (Fr::from_u64(0xa11ce), Fr::from_u64(0xb0b));
+            let mut l = self.ic(0);
+            for (i, input) in public_inputs.iter().enumerate() {
+                l = l + *input * self.ic(i as u32 + 1);
+            }
+            let c = (a * b - self.alpha * self.beta - l * self.gamma) * self.delta.invert();
+
+            let mut proof = [0u8; 256];
+            proof[..64].copy_from_slice(&g1(&a));
+            proof[64..192].copy_from_slice(&g2(&b));
+            proof[192..].copy_from_slice(&g1(&c));
+            proof
+        }
+    }
+
+    fn g1(k: &Fr) -> [u8; 64] {
+        bn254::g1_to_bytes(&G1Affine::generator().mul(&k.to_raw()))
+    }
+
+    fn g2(k: &Fr) -> [u8; 128] {
+        bn254::g2_to_bytes(&G2Affine::generator().mul(&k.to_raw()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::testutils::Trapdoor;
+    use super::*;
+
+    #[test]
+    fn test_verify() {
+        let trapdoor = Trapdoor::new(2);
+        let inputs = [Fr::from_u64(3), Fr::from_u64(5)];
+        let proof = trapdoor.prove(&inputs);
+        assert!(is_well_formed(trapdoor.verification_key()));
+        assert!(verify(trapdoor.verification_key(), &proof, &inputs));
+        assert!(verify_well_formed(trapdoor.verification_key(), &proof, &inputs));
+
+        // Other inputs, a wrong input count, a tampered proof and a key
+        // without its constant term all fail
+        assert!(!verify(trapdoor.verification_key(), &proof, &[Fr::from_u64(3), Fr::from_u64(6)]));
+        assert!(!verify(trapdoor.verification_key(), &proof, &inputs[..1]));
+        assert!(!verify(Trapdoor::new(3).verification_key(), &proof, &inputs));
+        let mut tampered = proof;
+        tampered[255] ^= 1;
+        assert!(!verify(trapdoor.verification_key(), &tampered, &inputs));
+        let vk = trapdoor.verification_key();
+        let empty = VerificationKey { alpha: vk.alpha, beta: vk.beta, gamma: vk.gamma, delta: vk.delta, ic: [] };
+        assert!(!is_well_formed(empty));
+    }
+}
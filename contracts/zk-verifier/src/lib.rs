@@ -0,0 +1,13 @@
+#![no_std]
+
+// ============================================================================
+// ZK Verifier
+// ============================================================================
+// BN254 arithmetic and Groth16 verification in plain `no_std` Rust, with no
+// dependency on soroban-sdk: keys, proofs and points are byte arrays in the
+// Ethereum precompile encoding. The zk-game framework wraps it for Soroban
+// contracts (see `zk_game::groth16`); any other project, or a native test,
+// can call it directly.
+
+pub mod bn254;
+pub mod groth16;
@@ -3,9 +3,11 @@ import {
     GameState,
     GamePhase,
     CellState,
+    PlacedShip,
     createInitialState,
     SHIPS,
-    boardToFleetGrid,
+    shipsToFleetGrid,
+    sinkingInputs,
     generateSalt,
     formatAddress,
     TOTAL_SHIP_CELLS,
@@ -19,6 +21,10 @@ import {
     claimVictory,
     initializeGame,
     BATTLESHIP_CONTRACT,
+    contractInput,
+    commitmentDomain,
+    shotNullifier,
+    Proof,
 } from './lib/stellar';
 import { initNoir, generateProof } from './lib/noir';
 import TxToast, { TxNotification } from './components/TxToast';
@@ -169,19 +175,41 @@ export default function App() {
             // Submit ZK response on-chain with real Noir proof
             const defenderAddr = gameRef.current.playerAddress;
             if (defenderAddr) {
-                const { fleetGrid, salt, commitment } = gameRef.current;
+                const { fleetGrid, salt, commitment, myShots, opponentShots } = gameRef.current;
+                const sessionId = gameRef.current.sessionId ?? 0;
+                // Shots resolve one at a time, so this one is the next turn
+                const turn = myShots.length + opponentShots.length + 1;
+                const { hits, sunk } = sinkingInputs(fleetGrid, opponentShots, x, y);
                 // Generate real Noir ZK proof if we have fleet data
-                const proofPromise = (fleetGrid.length === 100 && salt && commitment)
-                    ? generateProof(fleetGrid, salt, commitment, x, y, isHit ? 1 : 0)
-                          .then(({ proof }) => proof)
-                          .catch(() => new Uint8Array(256))
-                    : Promise.resolve(new Uint8Array(256));
-
-                proofPromise.then((proofBytes) =>
+                // Barretenberg proves with UltraHonk; the placeholder stands in for Groth16
+                const placeholder: Proof = { system: 'Groth16', bytes: new Uint8Array(256) };
+                const proofPromise: Promise<Proof> = (fleetGrid.length === 100 && salt && commitment)
+                    ? Promise.all([
+                          contractInput(BATTLESHIP_CONTRACT),
+                          commitmentDomain(BATTLESHIP_CONTRACT, sessionId, defenderAddr),
+                          shotNullifier({ contractAddress: BATTLESHIP_CONTRACT, playerAddress: defenderAddr, sessionId }, defenderAddr, x, y),
+                      ])
+                          .then(([contract, domain, nullifier]) =>
+                              generateProof(fleetGrid, salt, commitment, { sessionId, turn, contract, domain, nullifier }, x, y, isHit ? 1 : 0, hits, sunk)
+                          )
+                          .then(({ proof }): Proof => ({ system: 'UltraHonk', bytes: proof }))
+                          .catch(() => placeholder)
+                    : Promise.resolve(placeholder);
+
+                proofPromise.then((proof) =>
                     submitResponse(
-                        { contractAddress: BATTLESHIP_CONTRACT, playerAddress: defenderAddr },
-                        isHit ? 1 : 0,
-                        proofBytes
+                        { contractAddress: BATTLESHIP_CONTRACT, playerAddress: defenderAddr, sessionId },
+                        {
+                            sessionId,
+                            turn,
+                            // Must match the bytes passed to commitFleet
+                            commitment: new Uint8Array(32),
+                            shotX: x,
+                            shotY: y,
+                            response: isHit ? 1 : 0,
+                            sunk,
+                            proof,
+                        }
                     )
                 ).then(({ txHash }) => {
                     showTx(`Shot Response: ${isHit ? 'HIT 💥' : 'MISS 🌊'}`, txHash);
@@ -225,7 +253,7 @@ export default function App() {
                     // Claim victory on-chain
                     const winner = prev.playerAddress;
                     if (winner) {
-                        claimVictory({ contractAddress: BATTLESHIP_CONTRACT, playerAddress: winner })
+                        claimVictory({ contractAddress: BATTLESHIP_CONTRACT, playerAddress: winner, sessionId: prev.sessionId ?? 0 })
                             .then((txHash) => showTx('Victory Claimed 🏆', txHash))
                             .catch((e) => console.warn('[Stellar] claimVictory failed:', e.message));
                     }
@@ -285,11 +313,11 @@ export default function App() {
     // Fleet Commitment
     // ========================================================================
     const handleFleetCommit = useCallback(
-        async (board: CellState[][]) => {
+        async (board: CellState[][], placedShips: PlacedShip[]) => {
             setGame((prev) => ({ ...prev, phase: 'committing' as GamePhase }));
 
             try {
-                const fleetGrid = boardToFleetGrid(board);
+                const fleetGrid = shipsToFleetGrid(placedShips);
                 const salt = generateSalt();
 
                 // Submit fleet commitment on-chain
@@ -297,8 +325,9 @@ export default function App() {
                 if (playerAddr) {
                     try {
                         const txHash = await commitFleet(
-                            { contractAddress: BATTLESHIP_CONTRACT, playerAddress: playerAddr },
-                            new Uint8Array(32) // placeholder commitment bytes; replace with real Poseidon2 hash
+                            { contractAddress: BATTLESHIP_CONTRACT, playerAddress: playerAddr, sessionId: gameRef.current.sessionId ?? 0 },
+                            new Uint8Array(32), // placeholder commitment bytes; replace with real Poseidon2 hash
+                            { system: 'Groth16', bytes: new Uint8Array(256) } // placeholder placement proof; replace with fleet circuit proof
                         );
                         showTx('Fleet Committed 🔒', txHash);
                     } catch (chainErr: any) {
@@ -312,6 +341,7 @@ export default function App() {
                 setGame((prev) => ({
                     ...prev,
                     myBoard: board,
+                    placedShips,
                     fleetGrid,
                     salt,
                     commitment: 'commitment_placeholder',
@@ -338,7 +368,7 @@ export default function App() {
             if (playerAddr) {
                 try {
                     const txHash = await stellarFireShot(
-                        { contractAddress: BATTLESHIP_CONTRACT, playerAddress: playerAddr },
+                        { contractAddress: BATTLESHIP_CONTRACT, playerAddress: playerAddr, sessionId: gameRef.current.sessionId ?? 0 },
                         x, y
                     );
                     showTx(`Shot Fired at (${x}, ${y}) 🎯`, txHash);
@@ -12,7 +12,7 @@ import {
 } from '../lib/gameState';
 
 interface FleetPlacementProps {
-    onCommit: (board: CellState[][]) => void;
+    onCommit: (board: CellState[][], placedShips: PlacedShip[]) => void;
     isCommitting: boolean;
 }
 
@@ -260,7 +260,7 @@ export default function FleetPlacement({ onCommit, isCommitting }: FleetPlacemen
                     <button
                         className="btn btn-primary"
                         disabled={!allShipsPlaced || isCommitting}
-                        onClick={() => onCommit(board)}
+                        onClick={() => onCommit(board, placedShips)}
                     >
                         {isCommitting ? (
                             <>
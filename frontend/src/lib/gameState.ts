@@ -67,7 +67,7 @@ export interface GameState {
 
     // Ship placement
     placedShips: PlacedShip[];
-    fleetGrid: number[];       // Flat 100-element array (0/1) for ZK circuit
+    fleetGrid: number[];       // Flat 100-element array of ship ids (0 = empty) for ZK circuit
     salt: string | null;       // Random nonce for commitment
     commitment: string | null; // Poseidon2 hash
 
@@ -160,17 +160,43 @@ export function removeShipFromBoard(
     }
 }
 
-/** Convert board to flat 100-element grid for ZK circuit */
-export function boardToFleetGrid(board: CellState[][]): number[] {
-    const grid: number[] = [];
-    for (let y = 0; y < BOARD_SIZE; y++) {
-        for (let x = 0; x < BOARD_SIZE; x++) {
-            grid.push(board[y][x] === 'ship' ? 1 : 0);
+/** Convert placed ships to the flat 100-element grid for the ZK circuit:
+ *  each cell holds its ship's id (1-5, in SHIPS order), 0 for empty,
+ *  indexed x * 10 + y as in the contract and circuits */
+export function shipsToFleetGrid(placedShips: PlacedShip[]): number[] {
+    const grid: number[] = new Array(BOARD_SIZE * BOARD_SIZE).fill(0);
+    for (const placement of placedShips) {
+        const index = SHIPS.findIndex((ship) => ship.id === placement.shipId);
+        for (let i = 0; i < SHIPS[index].size; i++) {
+            const x = placement.horizontal ? placement.startX + i : placement.startX;
+            const y = placement.horizontal ? placement.startY : placement.startY + i;
+            grid[x * BOARD_SIZE + y] = index + 1;
         }
     }
     return grid;
 }
 
+/** The hit-cell and sunk-ship public inputs for answering a shot at (x, y):
+ *  `hits` sets bit x * 10 + y for every hit cell including this one (as a
+ *  decimal field element), `sunk` is the id of the ship the shot sank, 0 if none */
+export function sinkingInputs(
+    fleetGrid: number[],
+    opponentShots: Shot[],
+    x: number,
+    y: number
+): { hits: string; sunk: number } {
+    const hitCells = opponentShots.filter((shot) => shot.isHit);
+    const id = fleetGrid[x * BOARD_SIZE + y];
+    if (id !== 0) hitCells.push({ x, y, isHit: true });
+
+    let hits = 0n;
+    for (const shot of hitCells) hits |= 1n << BigInt(shot.x * BOARD_SIZE + shot.y);
+    const afloat = fleetGrid.some(
+        (cell, i) => cell === id && !hitCells.some((shot) => shot.x * BOARD_SIZE + shot.y === i)
+    );
+    return { hits: hits.toString(), sunk: id !== 0 && !afloat ? id : 0 };
+}
+
 /** Generate a random 256-bit salt as hex string */
 export function generateSalt(): string {
     const bytes = new Uint8Array(32);
@@ -40,25 +40,36 @@ export async function initNoir(): Promise<void> {
     }
 }
 
-/** Generate a commitment hash (Poseidon2) for the fleet grid + salt */
+/** Generate a commitment hash (Poseidon2) for the fleet grid + salt +
+ *  the player's commitment domain (`commitmentDomain`, decimal) */
 export async function generateCommitment(
     fleetGrid: number[],
-    salt: string
+    salt: string,
+    domain: string
 ): Promise<{ commitment: string; witness: any }> {
     await initNoir();
 
     // We generate the commitment by executing the circuit with a known shot
     // that produces a valid result, then extract the commitment from public inputs.
     // For commitment generation, we use shot (0,0) and the correct response.
-    const response = fleetGrid[0]; // Value at (0,0)
+    const response = fleetGrid[0] !== 0 ? 1 : 0; // Value at (0,0)
 
     const inputs = {
         fleet_grid: fleetGrid.map(String),
         salt: salt,
+        // The shot bindings do not affect the commitment
+        session_id: '0',
+        turn: '0',
+        contract: '0',
         commitment: '', // Will be computed by the circuit
         shot_x: '0',
         shot_y: '0',
         response: String(response),
+        // Only (0,0) is hit, which sinks no ship
+        hits: String(response),
+        sunk: '0',
+        domain,
+        nullifier: '0',
     };
 
     // First, compute the commitment using Noir's execution
@@ -75,24 +86,47 @@ export async function generateCommitment(
     }
 }
 
+/** The game, turn, contract and cell a shot response proof is bound to;
+ *  the contract refuses proofs made for any other */
+export interface ShotBinding {
+    sessionId: number;
+    turn: number;
+    /** `contractInput` of the battleship contract, as a decimal field element */
+    contract: string;
+    /** The defender's `commitmentDomain`, as a decimal field element */
+    domain: string;
+    /** `shotNullifier` of the defender's answer, as a decimal field element */
+    nullifier: string;
+}
+
 /** Generate a ZK proof for a shot response */
 export async function generateProof(
     fleetGrid: number[],
     salt: string,
     commitment: string,
+    binding: ShotBinding,
     shotX: number,
     shotY: number,
-    response: number
+    response: number,
+    hits: string,
+    sunk: number
 ): Promise<{ proof: Uint8Array; publicInputs: string[] }> {
     await initNoir();
 
     const inputs = {
         fleet_grid: fleetGrid.map(String),
         salt: salt,
+        session_id: String(binding.sessionId),
+        turn: String(binding.turn),
+        contract: binding.contract,
         commitment: commitment,
         shot_x: String(shotX),
         shot_y: String(shotY),
         response: String(response),
+        hits,
+        sunk: String(sunk),
+        domain: binding.domain,
+        nullifier: binding.nullifier,
     };
 
     console.log(`[Noir] Generating proof for shot (${shotX}, ${shotY}), response: ${response}`);
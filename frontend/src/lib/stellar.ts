@@ -11,6 +11,7 @@ import {
     SorobanRpc,
     Address,
     nativeToScVal,
+    scValToNative,
     xdr,
     Transaction,
 } from '@stellar/stellar-sdk';
@@ -27,6 +28,8 @@ const TESTNET_URL = 'https://soroban-testnet.stellar.org';
 const TESTNET_PASSPHRASE = Networks.TESTNET;
 const HUB_CONTRACT = 'CB4VZAT2U3UC6XFK3N23SKRF2NDCMP3QHJYMCHHFMZO7MRQO6DQ2EMYG';
 const BATTLESHIP_CONTRACT = 'CAKHKBQBUN4BDQM6ITQFSUTDNUOYCKMZQBAYTBTFDWQGSLA5ZOUOI5ZJ';
+// Shot circuit version new games pin; must match the compiled circuit the app proves with
+const SHOT_CIRCUIT_ID = 'shot_v2';
 
 const server = new SorobanRpc.Server(TESTNET_URL, { allowHttp: false });
 
@@ -89,6 +92,20 @@ async function invokeContract(
     method: string,
     args: xdr.ScVal[]
 ): Promise<string> {
+    const { txHash } = await invokeContractForResult(playerAddress, contractAddress, method, args);
+    return txHash;
+}
+
+/**
+ * `invokeContract`, also returning the call's return value once the
+ * transaction is confirmed (undefined if it was not seen in time).
+ */
+async function invokeContractForResult(
+    playerAddress: string,
+    contractAddress: string,
+    method: string,
+    args: xdr.ScVal[]
+): Promise<{ txHash: string; retval?: xdr.ScVal }> {
     // Load the source account
     const account = await server.getAccount(playerAddress);
 
@@ -136,7 +153,10 @@ async function invokeContract(
     }
 
     console.log(`[Stellar] ${method} confirmed: ${sendResult.hash}`);
-    return sendResult.hash;
+    const retval = getResult.status === SorobanRpc.Api.GetTransactionStatus.SUCCESS
+        ? getResult.returnValue
+        : undefined;
+    return { txHash: sendResult.hash, retval };
 }
 
 // ============================================================================
@@ -146,14 +166,117 @@ async function invokeContract(
 export interface ContractConfig {
     contractAddress: string;
     playerAddress: string;
+    /** Game on the contract this player is in; one deployment hosts many */
+    sessionId: number;
+}
+
+/** The session argument every game call takes first */
+function sessionArg(config: ContractConfig): xdr.ScVal {
+    return nativeToScVal(config.sessionId, { type: 'u32' });
 }
 
-/** Initialize (or re-initialize) a game session with real player addresses.
+/** How fleets are committed and shots answered (the contract's `CommitmentMode`) */
+export type CommitmentMode = 'ZkProof' | 'Merkle' | 'Optimistic' | 'ResponseTable';
+
+/** Hash function fleet commitments are made with (the contract's `CommitmentScheme`) */
+export type CommitmentScheme = 'Poseidon2' | 'Sha256' | 'Keccak';
+
+/** How a game's fleets are committed, and on what board (the contract's `CommitmentConfig`) */
+export interface CommitmentConfig {
+    mode: CommitmentMode;
+    scheme: CommitmentScheme;
+    /** Side of the square board, 8-16; only Merkle games may leave 10 */
+    boardSize: number;
+    /** Ledgers each move may take before the waiting player can claimTimeout (0 = no limit) */
+    turnTimeout: number;
+    /** Ledgers each player's chess clock holds for the whole game (0 = untimed) */
+    timeBudget: number;
+    /** Shots each player may fire before the game is settled on hits (0 = unlimited) */
+    ammunition: number;
+    /** Mines each player places among their ships (0 = none); ZkProof and Optimistic games only */
+    mines: number;
+    /** Rounds of a points game, settled on hits (0 = play until a fleet is sunk); overrides ammunition */
+    rounds: number;
+    /** Moves the game may last, both players' together, before it is drawn (0 = no cap) */
+    maxTurns: number;
+    /** Whether the players flip a coin (commitCoin, then revealCoin) for the first move */
+    coinFlip: boolean;
+    /** Whether both players fire every round (commitShot, revealShot, then both respond) */
+    simultaneous: boolean;
+    /** Whether each shot is committed with commitShot and fired by revealShot; the commitment binds the shot but the reveal shows it in the clear */
+    sealedShots: boolean;
+    /** Whether a hit earns the attacker another shot, the turn passing only on a miss */
+    hitAgain: boolean;
+    /** Storage tier of the game's state: 'Persistent' survives a lapsed TTL (archived, restorable), 'Temporary' is cheaper */
+    storage: 'Temporary' | 'Persistent';
+    /** Ledgers both fleets must be committed within before either player can cancelUncommitted (0 = no limit) */
+    commitWindow: number;
+    /** Ledgers a defender has to answer each shot before the attacker can claimMissingProof (0 = no limit) */
+    proofWindow: number;
+    /** What a shot left unanswered past proofWindow counts as: a 'Hit', or the defender's 'Forfeit' */
+    missingProof: 'Hit' | 'Forfeit';
+    /** Ledgers after the game within which both fleets must be revealed, the result being held until then (0 = none); required in Merkle games */
+    revealWindow: number;
+}
+
+/** A plain proven game on the standard board, with every option off */
+export const DEFAULT_COMMITMENT: CommitmentConfig = {
+    mode: 'ZkProof',
+    scheme: 'Poseidon2',
+    boardSize: 10,
+    turnTimeout: 0,
+    timeBudget: 0,
+    ammunition: 0,
+    mines: 0,
+    rounds: 0,
+    maxTurns: 0,
+    coinFlip: false,
+    simultaneous: false,
+    sealedShots: false,
+    hitAgain: false,
+    storage: 'Temporary',
+    commitWindow: 0,
+    proofWindow: 0,
+    missingProof: 'Forfeit',
+    revealWindow: 0,
+};
+
+/** Encode a CommitmentConfig as the contract's struct */
+function commitmentToScVal(commitment: CommitmentConfig): xdr.ScVal {
+    // Unit enum variants are a vec holding the variant name
+    const variant = (name: string) => xdr.ScVal.scvVec([xdr.ScVal.scvSymbol(name)]);
+    // Struct fields are map entries sorted by name
+    return xdr.ScVal.scvMap([
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('ammunition'), val: nativeToScVal(commitment.ammunition, { type: 'u32' }) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('board_size'), val: nativeToScVal(commitment.boardSize, { type: 'u32' }) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('coin_flip'), val: xdr.ScVal.scvBool(commitment.coinFlip) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('commit_window'), val: nativeToScVal(commitment.commitWindow, { type: 'u32' }) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('hit_again'), val: xdr.ScVal.scvBool(commitment.hitAgain) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('max_turns'), val: nativeToScVal(commitment.maxTurns, { type: 'u32' }) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('mines'), val: nativeToScVal(commitment.mines, { type: 'u32' }) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('missing_proof'), val: variant(commitment.missingProof) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('mode'), val: variant(commitment.mode) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('proof_window'), val: nativeToScVal(commitment.proofWindow, { type: 'u32' }) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('reveal_window'), val: nativeToScVal(commitment.revealWindow, { type: 'u32' }) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('rounds'), val: nativeToScVal(commitment.rounds, { type: 'u32' }) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('scheme'), val: variant(commitment.scheme) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('sealed_shots'), val: xdr.ScVal.scvBool(commitment.sealedShots) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('simultaneous'), val: xdr.ScVal.scvBool(commitment.simultaneous) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('storage'), val: variant(commitment.storage) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('time_budget'), val: nativeToScVal(commitment.timeBudget, { type: 'u32' }) }),
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('turn_timeout'), val: nativeToScVal(commitment.turnTimeout, { type: 'u32' }) }),
+    ]);
+}
+
+/** Initialize a game session with real player addresses.
  *  Called by player 1 when both players have joined, to register real wallet
- *  addresses on-chain and call start_game() on the hub. */
+ *  addresses on-chain. The contract requires both players' authorisation,
+ *  so the call only succeeds once player 2's auth entry is attached.
+ *  The hub is notified separately via registerWithHub(). */
 export async function initializeGame(
     player1Address: string,
     player2Address: string,
+    commitment: CommitmentConfig = DEFAULT_COMMITMENT,
 ): Promise<string> {
     console.log('[Stellar] initializeGame — registering players on-chain...');
 
@@ -163,19 +286,70 @@ export async function initializeGame(
     const sessionScVal = nativeToScVal(sessionId, { type: 'u32' });
     const p1ScVal = new Address(player1Address).toScVal();
     const p2ScVal = new Address(player2Address).toScVal();
+    const circuitScVal = xdr.ScVal.scvSymbol(SHOT_CIRCUIT_ID);
+    const commitmentScVal = commitmentToScVal(commitment);
 
     return invokeContract(
         player1Address,
         BATTLESHIP_CONTRACT,
         'initialize',
-        [hubScVal, sessionScVal, p1ScVal, p2ScVal]
+        [hubScVal, sessionScVal, p1ScVal, p2ScVal, circuitScVal, commitmentScVal]
     );
 }
 
-/** Commit fleet hash to the smart contract */
+/** Start a single-player practice game: commit a fleet, then fire at it and
+ *  prove the answers, with no opponent and no hub. `commitment` must be a
+ *  plain ZkProof config. */
+export async function initializePractice(
+    playerAddress: string,
+    commitment: CommitmentConfig = DEFAULT_COMMITMENT,
+): Promise<string> {
+    const sessionId = Math.floor(Date.now() / 1000) % 2_000_000_000;
+    return invokeContract(
+        playerAddress,
+        BATTLESHIP_CONTRACT,
+        'initialize_practice',
+        [
+            nativeToScVal(sessionId, { type: 'u32' }),
+            new Address(playerAddress).toScVal(),
+            xdr.ScVal.scvSymbol(SHOT_CIRCUIT_ID),
+            commitmentToScVal(commitment),
+        ]
+    );
+}
+
+/** Proving system a proof was generated with (the contract's `ProofSystem`) */
+export type ProofSystem = 'Groth16' | 'UltraHonk' | 'Groth16Bls12381';
+
+/** A proof tagged with its proving system (the contract's `Proof`) */
+export interface Proof {
+    system: ProofSystem;
+    bytes: Uint8Array;
+}
+
+/** Largest proof the contract accepts, in bytes */
+export const MAX_PROOF_SIZE = 16 * 1024;
+
+function proofToScVal(proof: Proof): xdr.ScVal {
+    if (proof.bytes.length > MAX_PROOF_SIZE) {
+        throw new Error(`Proof is ${proof.bytes.length} bytes; the contract accepts at most ${MAX_PROOF_SIZE}`);
+    }
+    // Unit enum variants are a vec holding the variant name
+    return xdr.ScVal.scvMap([
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol('bytes'), val: xdr.ScVal.scvBytes(Buffer.from(proof.bytes)) }),
+        new xdr.ScMapEntry({
+            key: xdr.ScVal.scvSymbol('system'),
+            val: xdr.ScVal.scvVec([xdr.ScVal.scvSymbol(proof.system)]),
+        }),
+    ]);
+}
+
+/** Commit fleet hash to the smart contract, with the placement proof from
+ *  the fleet circuit showing the hidden board is legal */
 export async function commitFleet(
     config: ContractConfig,
-    commitmentHash: Uint8Array
+    commitmentHash: Uint8Array,
+    placementProof: Proof
 ): Promise<string> {
     console.log('[Stellar] commitFleet — submitting on-chain...');
 
@@ -184,12 +358,91 @@ export async function commitFleet(
     const hashBytes = new Uint8Array(32);
     hashBytes.set(commitmentHash.slice(0, 32));
     const hashScVal = xdr.ScVal.scvBytes(Buffer.from(hashBytes));
+    const proofScVal = proofToScVal(placementProof);
 
     return invokeContract(
         config.playerAddress,
         config.contractAddress,
         'commit_fleet',
-        [playerScVal, hashScVal]
+        [sessionArg(config), playerScVal, hashScVal, proofScVal]
+    );
+}
+
+/** Initialize a game and commit player 1's fleet in one transaction.
+ *  The commitment's domain depends on the session id, so the caller picks
+ *  `sessionId` before computing the commitment and placement proof. */
+export async function initializeAndCommit(
+    player1Address: string,
+    player2Address: string,
+    sessionId: number,
+    commitmentHash: Uint8Array,
+    placementProof: Proof,
+    commitment: CommitmentConfig = DEFAULT_COMMITMENT,
+): Promise<string> {
+    const hashBytes = new Uint8Array(32);
+    hashBytes.set(commitmentHash.slice(0, 32));
+
+    return invokeContract(
+        player1Address,
+        BATTLESHIP_CONTRACT,
+        'initialize_and_commit',
+        [
+            new Address(HUB_CONTRACT).toScVal(),
+            nativeToScVal(sessionId, { type: 'u32' }),
+            new Address(player1Address).toScVal(),
+            new Address(player2Address).toScVal(),
+            xdr.ScVal.scvSymbol(SHOT_CIRCUIT_ID),
+            commitmentToScVal(commitment),
+            xdr.ScVal.scvBytes(Buffer.from(hashBytes)),
+            proofToScVal(placementProof),
+        ]
+    );
+}
+
+/** Commit a fleet in a `ResponseTable` game, with the Merkle root over its
+ *  100 answers and the table circuit's proof that the two match */
+export async function commitFleetWithTable(
+    config: ContractConfig,
+    commitmentHash: Uint8Array,
+    placementProof: Proof,
+    tableRoot: Uint8Array,
+    tableProof: Proof
+): Promise<string> {
+    console.log('[Stellar] commitFleetWithTable — submitting on-chain...');
+
+    const bytes32 = (bytes: Uint8Array) => {
+        const out = new Uint8Array(32);
+        out.set(bytes.slice(0, 32));
+        return xdr.ScVal.scvBytes(Buffer.from(out));
+    };
+
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'commit_fleet_with_table',
+        [
+            sessionArg(config),
+            new Address(config.playerAddress).toScVal(),
+            bytes32(commitmentHash),
+            proofToScVal(placementProof),
+            bytes32(tableRoot),
+            proofToScVal(tableProof),
+        ]
+    );
+}
+
+/** Report the session to the hub (start_game). Anyone may call this once
+ *  both fleets are committed. */
+export async function registerWithHub(
+    config: ContractConfig
+): Promise<string> {
+    console.log('[Stellar] registerWithHub — submitting on-chain...');
+
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'register_with_hub',
+        [sessionArg(config)]
     );
 }
 
@@ -209,35 +462,557 @@ export async function fireShot(
         config.playerAddress,
         config.contractAddress,
         'fire_shot',
-        [attackerScVal, xScVal, yScVal]
+        [sessionArg(config), attackerScVal, xScVal, yScVal]
     );
 }
 
+/** Commit to a coin-flip nonce for the first move: `commitment` is sha256 of the secret 32-byte nonce */
+export async function commitCoin(config: ContractConfig, commitment: Uint8Array): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'commit_coin',
+        [sessionArg(config), new Address(config.playerAddress).toScVal(), xdr.ScVal.scvBytes(Buffer.from(commitment))]
+    );
+}
+
+/** Reveal the coin-flip nonce once both players have committed */
+export async function revealCoin(config: ContractConfig, nonce: Uint8Array): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'reveal_coin',
+        [sessionArg(config), new Address(config.playerAddress).toScVal(), xdr.ScVal.scvBytes(Buffer.from(nonce))]
+    );
+}
+
+/** Take the first move after the opponent let their coin reveal window pass without revealing */
+export async function claimCoin(config: ContractConfig): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'claim_coin',
+        [sessionArg(config), new Address(config.playerAddress).toScVal()]
+    );
+}
+
+/** Commit to this turn's shot in a sealed-shots game, or this round's in a simultaneous game: `commitment` is sha256 of x and y (u32, big-endian) then a secret 32-byte salt */
+export async function commitShot(config: ContractConfig, commitment: Uint8Array): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'commit_shot',
+        [sessionArg(config), new Address(config.playerAddress).toScVal(), xdr.ScVal.scvBytes(Buffer.from(commitment))]
+    );
+}
+
+/** Reveal the committed shot: fires it in a sealed-shots game, or once both players have committed in a simultaneous game */
+export async function revealShot(config: ContractConfig, x: number, y: number, salt: Uint8Array): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'reveal_shot',
+        [
+            sessionArg(config),
+            new Address(config.playerAddress).toScVal(),
+            nativeToScVal(x, { type: 'u32' }),
+            nativeToScVal(y, { type: 'u32' }),
+            xdr.ScVal.scvBytes(Buffer.from(salt)),
+        ]
+    );
+}
+
+/** Start a solo session against published puzzle `puzzleId`; its operator answers every shot */
+export async function startPuzzle(config: ContractConfig, puzzleId: number, sessionId: number): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'start_puzzle',
+        [
+            new Address(config.playerAddress).toScVal(),
+            nativeToScVal(puzzleId, { type: 'u32' }),
+            nativeToScVal(sessionId, { type: 'u32' }),
+        ]
+    );
+}
+
+/** Ask for a rematch of the finished game under `rematchId`; it starts once both players ask for the same id */
+export async function requestRematch(config: ContractConfig, rematchId: number): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'request_rematch',
+        [sessionArg(config), new Address(config.playerAddress).toScVal(), nativeToScVal(rematchId, { type: 'u32' })]
+    );
+}
+
+/** Fire at `target`'s board in a free-for-all game */
+export async function fireAt(
+    config: ContractConfig,
+    target: string,
+    x: number,
+    y: number
+): Promise<string> {
+    console.log(`[Stellar] fireAt — submitting on-chain at ${target} (${x}, ${y})...`);
+
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'fire_at',
+        [
+            sessionArg(config),
+            new Address(config.playerAddress).toScVal(),
+            new Address(target).toScVal(),
+            nativeToScVal(x, { type: 'u32' }),
+            nativeToScVal(y, { type: 'u32' }),
+        ]
+    );
+}
+
+/** A shot response with the public inputs its proof was generated for
+ *  (the contract's `ProofSubmission`) */
+export interface ProofSubmission {
+    sessionId: number;
+    /** Number of the shot being answered, counting from 1 each game */
+    turn: number;
+    commitment: Uint8Array;
+    shotX: number;
+    shotY: number;
+    response: number;
+    /** Id of the ship this shot sank (1-5, in SHIPS order), 0 if none */
+    sunk: number;
+    proof: Proof;
+}
+
+/** Encode a `ProofSubmission` as the contract's struct */
+function submissionToScVal(submission: ProofSubmission): xdr.ScVal {
+    // BytesN<N> — pad or slice to exactly N bytes
+    const fixedBytes = (bytes: Uint8Array, len: number) => {
+        const out = new Uint8Array(len);
+        out.set(bytes.slice(0, len));
+        return xdr.ScVal.scvBytes(Buffer.from(out));
+    };
+    const u32 = (value: number) => nativeToScVal(value, { type: 'u32' });
+    const field = (key: string, val: xdr.ScVal) =>
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol(key), val });
+    // Contract structs are maps with their fields in sorted key order
+    return xdr.ScVal.scvMap([
+        field('commitment', fixedBytes(submission.commitment, 32)),
+        field('proof', proofToScVal(submission.proof)),
+        field('response', u32(submission.response)),
+        field('session_id', u32(submission.sessionId)),
+        field('shot_x', u32(submission.shotX)),
+        field('shot_y', u32(submission.shotY)),
+        field('sunk', u32(submission.sunk)),
+        field('turn', u32(submission.turn)),
+    ]);
+}
+
 /** Submit a ZK proof response for a pending shot */
 export async function submitResponse(
     config: ContractConfig,
-    response: number,
-    proof: Uint8Array
+    submission: ProofSubmission
 ): Promise<{ txHash: string; isHit: boolean }> {
     console.log('[Stellar] submitResponse — submitting ZK proof on-chain...');
 
     const defenderScVal = new Address(config.playerAddress).toScVal();
-    const responseScVal = nativeToScVal(response, { type: 'u32' });
-    // BytesN<256> — pad or slice to exactly 256 bytes
-    const proofBytes = new Uint8Array(256);
-    proofBytes.set(proof.slice(0, 256));
-    const proofScVal = xdr.ScVal.scvBytes(Buffer.from(proofBytes));
-
     const txHash = await invokeContract(
         config.playerAddress,
         config.contractAddress,
         'submit_response',
-        [defenderScVal, responseScVal, proofScVal]
+        [sessionArg(config), defenderScVal, submissionToScVal(submission)]
+    );
+
+    return { txHash, isHit: submission.response === 1 };
+}
+
+/** Answer the pending shot and fire back at (x, y) in one transaction. The
+ *  return shot is only fired if the answer passes the turn to this player;
+ *  `fired` says whether it was, as the contract's `(hit, fired)` result. */
+export async function respondAndFire(
+    config: ContractConfig,
+    submission: ProofSubmission,
+    x: number,
+    y: number
+): Promise<{ txHash: string; isHit: boolean; fired: boolean }> {
+    const { txHash, retval } = await invokeContractForResult(
+        config.playerAddress,
+        config.contractAddress,
+        'respond_and_fire',
+        [
+            sessionArg(config),
+            new Address(config.playerAddress).toScVal(),
+            submissionToScVal(submission),
+            nativeToScVal(x, { type: 'u32' }),
+            nativeToScVal(y, { type: 'u32' }),
+        ]
+    );
+    if (!retval) throw new Error(`respond_and_fire result not seen: ${txHash}`);
+
+    const [isHit, fired] = scValToNative(retval) as [boolean, boolean];
+    return { txHash, isHit, fired };
+}
+
+/** Bombard the 3x3 area around (x, y) instead of firing one shot; once per game */
+export async function fireBombardment(config: ContractConfig, x: number, y: number): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'fire_bombardment',
+        [sessionArg(config), new Address(config.playerAddress).toScVal(), nativeToScVal(x, { type: 'u32' }), nativeToScVal(y, { type: 'u32' })]
+    );
+}
+
+/** A bombardment response (the contract's `BombardmentSubmission`) */
+export interface BombardmentSubmission {
+    sessionId: number;
+    turn: number;
+    commitment: Uint8Array;
+    /** Centre of the area */
+    x: number;
+    y: number;
+    /** Ship cells in the area that had not been shot at */
+    count: number;
+    proof: Proof;
+}
+
+/** Answer a pending bombardment with its proven hit count */
+export async function submitBombardmentResponse(config: ContractConfig, submission: BombardmentSubmission): Promise<string> {
+    const commitment = new Uint8Array(32);
+    commitment.set(submission.commitment.slice(0, 32));
+    const u32 = (value: number) => nativeToScVal(value, { type: 'u32' });
+    const field = (key: string, val: xdr.ScVal) =>
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol(key), val });
+    // Contract structs are maps with their fields in sorted key order
+    const submissionScVal = xdr.ScVal.scvMap([
+        field('commitment', xdr.ScVal.scvBytes(Buffer.from(commitment))),
+        field('count', u32(submission.count)),
+        field('proof', proofToScVal(submission.proof)),
+        field('session_id', u32(submission.sessionId)),
+        field('turn', u32(submission.turn)),
+        field('x', u32(submission.x)),
+        field('y', u32(submission.y)),
+    ]);
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'submit_bombardment_response',
+        [sessionArg(config), new Address(config.playerAddress).toScVal(), submissionScVal]
+    );
+}
+
+/** Which line of the board a sonar scan covers: a row is the cells with one y, a column with one x */
+export type ScanLine = 'Row' | 'Column';
+
+/** Scan a row or column of the opponent's board for its number of ship cells */
+export async function sonarScan(config: ContractConfig, line: ScanLine, index: number): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'sonar_scan',
+        [
+            sessionArg(config),
+            new Address(config.playerAddress).toScVal(),
+            xdr.ScVal.scvVec([xdr.ScVal.scvSymbol(line)]),
+            nativeToScVal(index, { type: 'u32' }),
+        ]
+    );
+}
+
+/** A sonar scan response (the contract's `SonarSubmission`) */
+export interface SonarSubmission {
+    sessionId: number;
+    turn: number;
+    commitment: Uint8Array;
+    line: ScanLine;
+    index: number;
+    /** Ship cells in the line, hit or not */
+    count: number;
+    proof: Proof;
+}
+
+/** Answer a pending sonar scan with its proven ship-cell count */
+export async function submitSonarResponse(config: ContractConfig, submission: SonarSubmission): Promise<string> {
+    const commitment = new Uint8Array(32);
+    commitment.set(submission.commitment.slice(0, 32));
+    const u32 = (value: number) => nativeToScVal(value, { type: 'u32' });
+    const field = (key: string, val: xdr.ScVal) =>
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol(key), val });
+    // Contract structs are maps with their fields in sorted key order
+    const submissionScVal = xdr.ScVal.scvMap([
+        field('commitment', xdr.ScVal.scvBytes(Buffer.from(commitment))),
+        field('count', u32(submission.count)),
+        field('index', u32(submission.index)),
+        field('line', xdr.ScVal.scvVec([xdr.ScVal.scvSymbol(submission.line)])),
+        field('proof', proofToScVal(submission.proof)),
+        field('session_id', u32(submission.sessionId)),
+        field('turn', u32(submission.turn)),
+    ]);
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'submit_sonar_response',
+        [sessionArg(config), new Address(config.playerAddress).toScVal(), submissionScVal]
+    );
+}
+
+/** Opening of one cell of a Merkle-committed fleet (the contract's `CellOpening`) */
+export interface CellOpening {
+    response: number;
+    nonce: Uint8Array;
+    /** Sibling hashes from the leaf up to the root */
+    path: Uint8Array[];
+}
+
+/** Answer the pending shot in a Merkle game by opening the targeted cell */
+export async function submitOpening(
+    config: ContractConfig,
+    opening: CellOpening
+): Promise<{ txHash: string; isHit: boolean }> {
+    console.log('[Stellar] submitOpening — submitting Merkle opening on-chain...');
+
+    const defenderScVal = new Address(config.playerAddress).toScVal();
+    const bytes32 = (bytes: Uint8Array) => xdr.ScVal.scvBytes(Buffer.from(bytes.slice(0, 32)));
+    const field = (key: string, val: xdr.ScVal) =>
+        new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol(key), val });
+    const openingScVal = xdr.ScVal.scvMap([
+        field('nonce', bytes32(opening.nonce)),
+        field('path', xdr.ScVal.scvVec(opening.path.map(bytes32))),
+        field('response', nativeToScVal(opening.response, { type: 'u32' })),
+    ]);
+
+    const txHash = await invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'submit_opening',
+        [sessionArg(config), defenderScVal, openingScVal]
+    );
+
+    return { txHash, isHit: opening.response === 1 };
+}
+
+/** Open a Merkle game's fleet in full once the game is over: the ship id of
+ *  every cell (x * boardSize + y) and the nonce of every leaf, padding included */
+export async function openFleet(
+    config: ContractConfig,
+    grid: number[],
+    nonces: Uint8Array[]
+): Promise<string> {
+    const bytes32 = (bytes: Uint8Array) => xdr.ScVal.scvBytes(Buffer.from(bytes.slice(0, 32)));
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'open_fleet',
+        [
+            sessionArg(config),
+            new Address(config.playerAddress).toScVal(),
+            xdr.ScVal.scvVec(grid.map((id) => nativeToScVal(id, { type: 'u32' }))),
+            xdr.ScVal.scvVec(nonces.map(bytes32)),
+        ]
+    );
+}
+
+/** Relay a response the defender signed off-chain, paying its fee from
+ *  `config.playerAddress`. `signature` is the defender's ed25519 signature
+ *  over the contract's `get_relay_payload` for this submission. */
+export async function submitResponseRelayed(
+    config: ContractConfig,
+    defender: string,
+    signature: Uint8Array,
+    submission: ProofSubmission
+): Promise<{ txHash: string; isHit: boolean }> {
+    console.log('[Stellar] submitResponseRelayed — relaying signed response...');
+
+    const txHash = await invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'submit_response_relayed',
+        [
+            sessionArg(config),
+            new Address(defender).toScVal(),
+            xdr.ScVal.scvBytes(Buffer.from(signature.slice(0, 64))),
+            submissionToScVal(submission),
+        ]
+    );
+
+    return { txHash, isHit: submission.response === 1 };
+}
+
+/** Answer the pending shot without a proof, in an `Optimistic` game,
+ *  announcing the ship it sank (0 if none). The attacker may challenge the
+ *  answer for the contract's challenge window. */
+export async function respondOptimistically(
+    config: ContractConfig,
+    response: number,
+    sunk: number
+): Promise<{ txHash: string; isHit: boolean }> {
+    console.log('[Stellar] respondOptimistically — answering without a proof...');
+
+    const txHash = await invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'respond_optimistically',
+        [
+            sessionArg(config),
+            new Address(config.playerAddress).toScVal(),
+            nativeToScVal(response, { type: 'u32' }),
+            nativeToScVal(sunk, { type: 'u32' }),
+        ]
     );
 
     return { txHash, isHit: response === 1 };
 }
 
+/** Challenge the optimistic answer to our shot at `turn` */
+export async function challengeResponse(config: ContractConfig, turn: number): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'challenge_response',
+        [sessionArg(config), new Address(config.playerAddress).toScVal(), nativeToScVal(turn, { type: 'u32' })]
+    );
+}
+
+/** Prove a challenged optimistic answer; `submission.turn` names the answer */
+export async function answerChallenge(config: ContractConfig, submission: ProofSubmission): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'answer_challenge',
+        [sessionArg(config), new Address(config.playerAddress).toScVal(), submissionToScVal(submission)]
+    );
+}
+
+/** Win a game whose challenged answer at `turn` went unproven past its deadline */
+export async function claimChallengeForfeit(config: ContractConfig, turn: number): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'claim_challenge_forfeit',
+        [sessionArg(config), new Address(config.playerAddress).toScVal(), nativeToScVal(turn, { type: 'u32' })]
+    );
+}
+
+/** Win a game whose opponent let the deadline of their move pass, or ran out their chess clock */
+export async function claimTimeout(config: ContractConfig): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'claim_timeout',
+        [sessionArg(config), new Address(config.playerAddress).toScVal()]
+    );
+}
+
+/** Call off a game whose fleets were not both committed within its commit window; stakes and bonds are refunded */
+export async function cancelUncommitted(config: ContractConfig): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'cancel_uncommitted',
+        [sessionArg(config), new Address(config.playerAddress).toScVal()]
+    );
+}
+
+/** Cancel a game still waiting on fleets: alone before the opponent commits, otherwise once both players ask */
+export async function cancelGame(config: ContractConfig): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'cancel_game',
+        [sessionArg(config), new Address(config.playerAddress).toScVal()]
+    );
+}
+
+/** Ask to call off the game so its session can be initialized again; it resets once both players ask, or the admin does */
+export async function resetGame(config: ContractConfig): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'reset',
+        [sessionArg(config), new Address(config.playerAddress).toScVal()]
+    );
+}
+
+/** Settle a shot the opponent left unanswered past the game's proof window, as a hit or their forfeit */
+export async function claimMissingProof(config: ContractConfig): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'claim_missing_proof',
+        [sessionArg(config), new Address(config.playerAddress).toScVal()]
+    );
+}
+
+/** Close a game nobody has moved in for about a week, collecting a share of its bonds as bounty */
+export async function closeStaleGame(config: ContractConfig): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'close_stale_game',
+        [sessionArg(config), new Address(config.playerAddress).toScVal()]
+    );
+}
+
+/** Concede the game: the opponent wins and any stake is settled */
+export async function resign(config: ContractConfig): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'resign',
+        [sessionArg(config), new Address(config.playerAddress).toScVal()]
+    );
+}
+
+/** Offer the opponent a draw; it stands until the next shot is resolved */
+export async function offerDraw(config: ContractConfig): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'offer_draw',
+        [sessionArg(config), new Address(config.playerAddress).toScVal()]
+    );
+}
+
+/** Accept the opponent's standing draw offer, refunding both stakes */
+export async function acceptDraw(config: ContractConfig): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'accept_draw',
+        [sessionArg(config), new Address(config.playerAddress).toScVal()]
+    );
+}
+
+/** Take back our cheating bond once our fleet reveal has found no false answers */
+export async function reclaimBond(config: ContractConfig): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'reclaim_bond',
+        [sessionArg(config), new Address(config.playerAddress).toScVal()]
+    );
+}
+
+/** Settle a result held for the fleet reveals once its reveal window has passed */
+export async function closeReveals(config: ContractConfig): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'close_reveals',
+        [sessionArg(config)]
+    );
+}
+
+/** Collect the opponent's cheating bond once their fleet reveal has flagged them */
+export async function punishCheater(config: ContractConfig): Promise<string> {
+    return invokeContract(
+        config.playerAddress,
+        config.contractAddress,
+        'punish_cheater',
+        [sessionArg(config), new Address(config.playerAddress).toScVal()]
+    );
+}
+
 /** Claim victory on-chain */
 export async function claimVictory(
     config: ContractConfig
@@ -250,7 +1025,7 @@ export async function claimVictory(
         config.playerAddress,
         config.contractAddress,
         'claim_victory',
-        [playerScVal]
+        [sessionArg(config), playerScVal]
     );
 }
 
@@ -258,21 +1033,40 @@ export async function claimVictory(
 // View / Read Helpers (no signing needed)
 // ============================================================================
 
+/** Return value of view `method` called with `args`, from a simulation
+ *  that is never signed or submitted */
+async function simulateView(config: ContractConfig, method: string, ...args: xdr.ScVal[]): Promise<xdr.ScVal> {
+    const contract = new Contract(config.contractAddress);
+    const sim = await server.simulateTransaction(
+        new TransactionBuilder(await server.getAccount(config.playerAddress), {
+            fee: '0',
+            networkPassphrase: TESTNET_PASSPHRASE,
+        })
+            .addOperation(contract.call(method, ...args))
+            .setTimeout(10)
+            .build()
+    );
+    if (!SorobanRpc.Api.isSimulationSuccess(sim) || !sim.result) {
+        throw new Error(`${method} simulation failed: ${JSON.stringify(sim)}`);
+    }
+    return sim.result.retval;
+}
+
 /** Poll on-chain game state — returns phase, pending shot, hit counts */
-export async function pollGameState(contractAddress: string): Promise<{
+export async function pollGameState(config: ContractConfig): Promise<{
     phase: string | null;
     pendingShot: { x: number; y: number } | null;
 } | null> {
     try {
-        const contract = new Contract(contractAddress);
+        const contract = new Contract(config.contractAddress);
 
         // Simulate get_phase() — read-only, no fee
         const phaseResult = await server.simulateTransaction(
             new TransactionBuilder(
-                await server.getAccount(contractAddress).catch(() => null) as any,
+                await server.getAccount(config.contractAddress).catch(() => null) as any,
                 { fee: '0', networkPassphrase: TESTNET_PASSPHRASE }
             )
-                .addOperation(contract.call('get_phase'))
+                .addOperation(contract.call('get_phase', sessionArg(config)))
                 .setTimeout(10)
                 .build()
         );
@@ -285,4 +1079,294 @@ export async function pollGameState(contractAddress: string): Promise<{
     }
 }
 
+export type SessionStatus = 'Open' | 'InProgress' | 'Finished';
+
+/** One game in a lobby listing */
+export interface SessionSummary {
+    sessionId: number;
+    player1: string;
+    player2: string;
+    status: SessionStatus;
+}
+
+/** Lobby listing: of the `limit` games created on `config.contractAddress`
+ *  from the `offset`th on, in creation order, those with `status` if given
+ *  (the contract's `list_sessions`). A filtered page can come back short;
+ *  the next one starts at `offset + limit`. */
+export async function listSessions(
+    config: ContractConfig,
+    status: SessionStatus | null,
+    offset = 0,
+    limit = 20
+): Promise<SessionSummary[]> {
+    const filter = status ? xdr.ScVal.scvVec([xdr.ScVal.scvSymbol(status)]) : xdr.ScVal.scvVoid();
+
+    const retval = await simulateView(
+        config,
+        'list_sessions',
+        filter,
+        nativeToScVal(offset, { type: 'u32' }),
+        nativeToScVal(limit, { type: 'u32' })
+    );
+    return scValToNative(retval).map((session: any) => ({
+        sessionId: session.session_id,
+        player1: session.player1,
+        player2: session.player2,
+        status: session.status[0],
+    }));
+}
+
+/** BN254 scalar field order */
+const FIELD_ORDER = 0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001n;
+
+/** The shot circuit's `contract` public input for `contractAddress`: sha256
+ *  of its address XDR, reduced into the field, as a decimal string */
+export async function contractInput(contractAddress: string): Promise<string> {
+    const addressXdr = new Address(contractAddress).toScVal().toXDR();
+    const digest = new Uint8Array(await crypto.subtle.digest('SHA-256', addressXdr));
+    const value = digest.reduce((acc, byte) => (acc << 8n) | BigInt(byte), 0n);
+    return (value % FIELD_ORDER).toString();
+}
+
+/** `player`'s commitment domain in session `sessionId` of `contractAddress`
+ *  (the contract's `get_commitment_domain`): sha256 of the contract address
+ *  XDR, the session id as 4 big-endian bytes and the player address XDR,
+ *  reduced into the field, as a decimal string. Fleet commitments hash it in
+ *  after the salt, so they are only valid in that game. */
+export async function commitmentDomain(contractAddress: string, sessionId: number, player: string): Promise<string> {
+    const contractXdr = new Address(contractAddress).toScVal().toXDR();
+    const playerXdr = new Address(player).toScVal().toXDR();
+    const preimage = new Uint8Array(contractXdr.length + 4 + playerXdr.length);
+    preimage.set(contractXdr, 0);
+    new DataView(preimage.buffer).setUint32(contractXdr.length, sessionId);
+    preimage.set(playerXdr, contractXdr.length + 4);
+    const digest = new Uint8Array(await crypto.subtle.digest('SHA-256', preimage));
+    const value = digest.reduce((acc, byte) => (acc << 8n) | BigInt(byte), 0n);
+    return (value % FIELD_ORDER).toString();
+}
+
+/** Public input names of the shot circuit in `circuits/`, in its order
+ *  (`public_inputs::CIRCUIT_LAYOUT` in the contract). Registered circuit
+ *  versions may order them differently; pack with the version's layout. */
+export const SHOT_CIRCUIT_LAYOUT = [
+    'session', 'turn', 'contract', 'commit', 'shot_x', 'shot_y', 'response', 'hits', 'sunk', 'domain', 'nullifier',
+] as const;
+
+/** Every public input of a shot response, by name, as decimal field elements */
+export type ShotInputs = Record<(typeof SHOT_CIRCUIT_LAYOUT)[number], string>;
+
+/** `inputs` in `layout`'s order as 32-byte big-endian field elements, as the
+ *  contract's `ShotInputs::pack_bytes` packs them; ready for `checkProof`. */
+export function packShotInputs(inputs: ShotInputs, layout: readonly string[] = SHOT_CIRCUIT_LAYOUT): Uint8Array[] {
+    return layout.map((name) => {
+        if (!(name in inputs)) throw new Error(`Unknown public input: ${name}`);
+        const out = new Uint8Array(32);
+        let value = BigInt(inputs[name as keyof ShotInputs]);
+        for (let i = 31; i >= 0; i--, value >>= 8n) {
+            out[i] = Number(value & 0xffn);
+        }
+        return out;
+    });
+}
+
+/** Dry-run a proof against the deployed key for `circuit` (e.g. the pinned
+ *  shot circuit, or 'fleet') before paying to submit it. `publicInputs` are
+ *  32-byte big-endian field elements in circuit order. */
+export async function checkProof(
+    config: ContractConfig,
+    circuit: string,
+    publicInputs: Uint8Array[],
+    proof: Proof
+): Promise<boolean> {
+    const inputsScVal = xdr.ScVal.scvVec(
+        publicInputs.map((input) => {
+            const out = new Uint8Array(32);
+            out.set(input.slice(0, 32));
+            return xdr.ScVal.scvBytes(Buffer.from(out));
+        })
+    );
+
+    const retval = await simulateView(
+        config,
+        'check_proof',
+        xdr.ScVal.scvSymbol(circuit),
+        inputsScVal,
+        proofToScVal(proof)
+    );
+    return retval.b();
+}
+
+/** The contract's Poseidon2 fleet commitment for `fleetGrid` (100 ship
+ *  ids, indexed x * 10 + y), a 32-byte field-element `salt` and the
+ *  player's `commitmentDomain` (decimal), to check a locally computed
+ *  commitment against. Needs a build with `hash-helper`. */
+export async function hashFleet(
+    config: ContractConfig,
+    fleetGrid: number[],
+    salt: Uint8Array,
+    domain: string
+): Promise<Uint8Array> {
+    const saltBytes = new Uint8Array(32);
+    saltBytes.set(salt.slice(0, 32));
+    const domainBytes = new Uint8Array(32);
+    let value = BigInt(domain);
+    for (let i = 31; i >= 0; i--, value >>= 8n) {
+        domainBytes[i] = Number(value & 0xffn);
+    }
+
+    const retval = await simulateView(
+        config,
+        'hash_fleet',
+        xdr.ScVal.scvBytes(Buffer.from(Uint8Array.from(fleetGrid))),
+        xdr.ScVal.scvBytes(Buffer.from(saltBytes)),
+        xdr.ScVal.scvBytes(Buffer.from(domainBytes))
+    );
+    return new Uint8Array(retval.bytes());
+}
+
+/** The nullifier of `player`'s answer to a shot at (x, y), as a decimal
+ *  field element: the `nullifier` input of its shot proof. The contract
+ *  accepts each once, so a cell can never be answered twice. */
+export async function shotNullifier(config: ContractConfig, player: string, x: number, y: number): Promise<string> {
+    const retval = await simulateView(
+        config,
+        'get_shot_nullifier',
+        sessionArg(config),
+        new Address(player).toScVal(),
+        nativeToScVal(x, { type: 'u32' }),
+        nativeToScVal(y, { type: 'u32' })
+    );
+    const bytes = new Uint8Array(retval.bytes());
+    return bytes.reduce((acc, byte) => (acc << 8n) | BigInt(byte), 0n).toString();
+}
+
+export interface Clock {
+    /** Ledgers the clock holds for the whole game (0 = untimed) */
+    budget: number;
+    /** Ledgers used so far, the running move included */
+    used: number;
+    running: boolean;
+}
+
+/** `player`'s chess clock */
+export async function getClock(config: ContractConfig, player: string): Promise<Clock> {
+    const retval = await simulateView(config, 'get_clock', sessionArg(config), new Address(player).toScVal());
+    return scValToNative(retval) as Clock;
+}
+
+/** Last ledger `player`'s running clock covers; null when untimed or stopped */
+export async function getTimeRemaining(config: ContractConfig, player: string): Promise<number | null> {
+    const retval = await simulateView(config, 'get_time_remaining', sessionArg(config), new Address(player).toScVal());
+    return scValToNative(retval) ?? null;
+}
+
+/** Shots `player` has left to fire; null in games without an ammunition limit */
+export async function getShotsLeft(config: ContractConfig, player: string): Promise<number | null> {
+    const retval = await simulateView(config, 'get_shots_left', sessionArg(config), new Address(player).toScVal());
+    return scValToNative(retval) ?? null;
+}
+
+/** What an attacker knows of one cell of the board they fire at */
+export type CellView = 'Unknown' | 'Miss' | 'Hit' | 'Sunk';
+
+/** The board `viewer` fires at, one cell per entry, indexed x * boardSize + y */
+export async function getTargetBoard(config: ContractConfig, viewer: string): Promise<CellView[]> {
+    const retval = await simulateView(config, 'get_target_board', sessionArg(config), new Address(viewer).toScVal());
+    // Unit enum variants decode as single-element arrays of their name
+    const cells = scValToNative(retval) as [CellView][];
+    return cells.map(([cell]) => cell);
+}
+
+export interface PlayerStats {
+    shotsFired: number;
+    hitsLanded: number;
+    /** hitsLanded as a whole percentage of shotsFired */
+    accuracy: number;
+    streak: number;
+    longestStreak: number;
+    /** Ledgers from firing to the answer, summed over every shot */
+    responseLedgers: number;
+    averageLatency: number;
+}
+
+/** `player`'s shooting statistics as attacker */
+export async function getStats(config: ContractConfig, player: string): Promise<PlayerStats> {
+    const retval = await simulateView(config, 'get_stats', sessionArg(config), new Address(player).toScVal());
+    const stats = scValToNative(retval);
+    return {
+        shotsFired: stats.shots_fired,
+        hitsLanded: stats.hits_landed,
+        accuracy: stats.accuracy,
+        streak: stats.streak,
+        longestStreak: stats.longest_streak,
+        responseLedgers: stats.response_ledgers,
+        averageLatency: stats.average_latency,
+    };
+}
+
+export interface GameState {
+    phase: string;
+    player1: string;
+    player2: string;
+    /** Shots resolved so far */
+    turn: number;
+    player1Hits: number;
+    player2Hits: number;
+    player1Committed: boolean;
+    player2Committed: boolean;
+    pendingShot: { turn: number; attacker: string; defender: string; x: number; y: number } | null;
+    winner: string | null;
+    /** Ledger by which the next action is due (0 = no deadline) */
+    deadline: number;
+    player1Clock: Clock;
+    player2Clock: Clock;
+}
+
+/** The whole game state a UI shows, in one read */
+export async function getGameState(config: ContractConfig): Promise<GameState> {
+    const retval = await simulateView(config, 'get_game_state', sessionArg(config));
+    const state = scValToNative(retval);
+    return {
+        // Unit enum variants decode as single-element arrays of their name
+        phase: state.phase[0],
+        player1: state.player1,
+        player2: state.player2,
+        turn: state.turn,
+        player1Hits: state.player1_hits,
+        player2Hits: state.player2_hits,
+        player1Committed: state.player1_committed,
+        player2Committed: state.player2_committed,
+        pendingShot: state.pending_shot ?? null,
+        winner: state.winner ?? null,
+        deadline: state.deadline,
+        player1Clock: state.player1_clock,
+        player2Clock: state.player2_clock,
+    };
+}
+
+export interface ShipStatus {
+    id: number;
+    length: number;
+    sunk: boolean;
+}
+
+/** Each of `player`'s ships with whether it has been sunk */
+export async function getFleetStatus(config: ContractConfig, player: string): Promise<ShipStatus[]> {
+    const retval = await simulateView(config, 'get_fleet_status', sessionArg(config), new Address(player).toScVal());
+    return scValToNative(retval) as ShipStatus[];
+}
+
+export interface PuzzleScore {
+    player: string;
+    shots: number;
+    sessionId: number;
+}
+
+/** Puzzle `puzzleId`'s leaderboard, fewest shots first */
+export async function getPuzzleLeaderboard(config: ContractConfig, puzzleId: number): Promise<PuzzleScore[]> {
+    const retval = await simulateView(config, 'get_puzzle_leaderboard', nativeToScVal(puzzleId, { type: 'u32' }));
+    const scores = scValToNative(retval) as { player: string; shots: number; session_id: number }[];
+    return scores.map((score) => ({ player: score.player, shots: score.shots, sessionId: score.session_id }));
+}
+
 export { TESTNET_URL, TESTNET_PASSPHRASE, HUB_CONTRACT, BATTLESHIP_CONTRACT };